@@ -0,0 +1,98 @@
+//! Computing the next version of a project
+//!
+//! Shared by the `bump` command, which applies the version, and the `next`
+//! command, which only prints it.
+
+use crate::git;
+use crate::project::Project;
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use semver::{BuildMetadata, Prerelease, Version};
+use std::path::Path;
+
+/// How to derive the next version from the current one
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum BumpType {
+    Major,
+    Minor,
+    Patch,
+    /// Increment the prerelease number (`1.2.3-rc.1` → `1.2.3-rc.2`), or start
+    /// a prerelease of the next patch version (`1.2.3` → `1.2.4-0`)
+    Prerelease,
+    /// Choose major, minor or patch from the conventional commit messages since
+    /// the current version's tag
+    Auto,
+}
+
+/// Compute the version after `current` for a project in `dir`
+pub fn next_version(
+    project: &dyn Project,
+    dir: &Path,
+    current: &Version,
+    bump_type: BumpType,
+) -> Result<Version> {
+    let next = match bump_type {
+        BumpType::Major => Version::new(current.major + 1, 0, 0),
+        BumpType::Minor => Version::new(current.major, current.minor + 1, 0),
+        BumpType::Patch => Version::new(current.major, current.minor, current.patch + 1),
+        BumpType::Prerelease => next_prerelease(current)?,
+        BumpType::Auto => {
+            let tag = project.get_tag_name(current);
+            let messages = git::commit_messages_since(dir, &tag)?;
+            if messages.is_empty() {
+                return Err(anyhow!("No commits since {tag}; nothing to release"));
+            }
+            let bump_type = conventional_bump_type(&messages);
+            return next_version(project, dir, current, bump_type);
+        }
+    };
+    Ok(next)
+}
+
+// npm-style prerelease increment: bump the last numeric identifier, or append
+// `.0` if there is none
+fn next_prerelease(current: &Version) -> Result<Version> {
+    if current.pre.is_empty() {
+        let mut next = Version::new(current.major, current.minor, current.patch + 1);
+        next.pre = Prerelease::new("0")?;
+        return Ok(next);
+    }
+
+    let mut identifiers: Vec<String> = current.pre.split('.').map(str::to_string).collect();
+    match identifiers
+        .iter_mut()
+        .rev()
+        .find_map(|id| id.parse::<u64>().ok().map(|n| (id, n)))
+    {
+        Some((id, n)) => *id = (n + 1).to_string(),
+        None => identifiers.push("0".to_string()),
+    }
+
+    let mut next = current.clone();
+    next.pre = Prerelease::new(&identifiers.join("."))?;
+    next.build = BuildMetadata::EMPTY;
+    Ok(next)
+}
+
+/// The bump implied by conventional commit messages: major for breaking
+/// changes, minor for features, and patch otherwise
+pub fn conventional_bump_type(messages: &[String]) -> BumpType {
+    let header_re = regex::Regex::new(r"^(\w+)(?:\([^)]*\))?(!)?:").unwrap();
+
+    let mut bump_type = BumpType::Patch;
+    for message in messages {
+        let header = message.lines().next().unwrap_or_default();
+        let caps = header_re.captures(header);
+        let breaking = caps.as_ref().is_some_and(|caps| caps.get(2).is_some())
+            || message.lines().any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            });
+        if breaking {
+            return BumpType::Major;
+        }
+        if caps.is_some_and(|caps| &caps[1] == "feat") {
+            bump_type = BumpType::Minor;
+        }
+    }
+    bump_type
+}
@@ -1,4 +1,5 @@
-use anyhow::{Context, Result};
+use crate::diff::FileChange;
+use crate::error::{Error, Result};
 use chrono::Local;
 use log::{debug, warn};
 use regex::Regex;
@@ -46,47 +47,8 @@ pub fn find_changelog(dir: &str) -> Option<PathBuf> {
 
 /// Update the changelog by replacing the unreleased section with the new version
 pub fn update_changelog(path: &Path, version: &Version) -> Result<()> {
-    let content = fs::read_to_string(path).context("Failed to read changelog file")?;
-
-    let today = Local::now().format("%Y-%m-%d").to_string();
-
-    // Try to find and replace an unreleased section using regex
-    let mut new_content = content.clone();
-    let mut found = false;
-
-    // Format the version header
-    let version_header = format!("## [{version}] - {today}");
-
-    // Try each pattern until one matches
-    for pattern in &UNRELEASED_PATTERNS {
-        // Compile the regex with proper flags
-        let re = match Regex::new(pattern) {
-            Ok(re) => re,
-            Err(e) => {
-                warn!("Invalid regex pattern: {pattern} - {e}");
-                continue;
-            }
-        };
-
-        // Check if this pattern matches
-        if re.is_match(&content) {
-            // It matched! Replace the first occurrence only
-            new_content = re.replace(&content, &version_header).to_string();
-            found = true;
-            debug!("Matched pattern: {pattern} - replacing with: {version_header}");
-            break;
-        }
-    }
-
-    if !found {
-        warn!(
-            "No unreleased section found in changelog at {}",
-            path.display()
-        );
-        // We'll just keep the file as is to avoid incorrect modifications
-    } else {
-        // Write the updated content back to the file
-        fs::write(path, new_content).context("Failed to write updated changelog")?;
+    if let Some(change) = dry_run_update_changelog(path, version)? {
+        change.apply()?;
         debug!(
             "Updated unreleased section to version {} in {}",
             version,
@@ -97,14 +59,17 @@ pub fn update_changelog(path: &Path, version: &Version) -> Result<()> {
     Ok(())
 }
 
-/// Preview the changelog update without making changes (dry run)
-pub fn dry_run_update_changelog(path: &Path, version: &Version) -> Result<String> {
-    let content = fs::read_to_string(path).context("Failed to read changelog file")?;
+/// Compute the new content of the changelog without making changes (dry run).
+/// Returns `None` if the changelog has no unreleased section.
+pub fn dry_run_update_changelog(path: &Path, version: &Version) -> Result<Option<FileChange>> {
+    let content = fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
 
     let today = Local::now().format("%Y-%m-%d").to_string();
-    let mut diff = String::new();
-    let mut found = false;
 
+    // Format the version header
     let version_header = format!("## [{version}] - {today}");
 
     // Try each pattern until one matches
@@ -118,26 +83,40 @@ pub fn dry_run_update_changelog(path: &Path, version: &Version) -> Result<String
             }
         };
 
-        if let Some(m) = re.find(&content) {
-            let header = m.as_str();
-
-            diff = format!(
-                "Would update changelog {}:\n  {} → {}",
-                path.display(),
-                header.trim(),
-                version_header
-            );
-            found = true;
-            break;
+        // Check if this pattern matches
+        if re.is_match(&content) {
+            // It matched! Replace the first occurrence only
+            let new_content = re.replace(&content, &version_header).to_string();
+            debug!("Matched pattern: {pattern} - replacing with: {version_header}");
+            return Ok(Some(FileChange::new(path, content, new_content)));
         }
     }
 
-    if !found {
-        diff = format!(
-            "No unreleased section found in changelog {}",
-            path.display()
-        );
-    }
+    // We'll just keep the file as is to avoid incorrect modifications
+    warn!(
+        "No unreleased section found in changelog at {}",
+        path.display()
+    );
+    Ok(None)
+}
 
-    Ok(diff)
+/// Get the version of the most recent release heading in the changelog, e.g.
+/// `## [1.2.3] - 2024-01-01`, skipping the unreleased section
+pub fn latest_release_version(path: &Path) -> Result<Option<Version>> {
+    let content = fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let heading_re = Regex::new(r"(?m)^##\s*\[?v?(\d+\.\d+\.\d+[^\]\s]*)").unwrap();
+    let Some(caps) = heading_re.captures(&content) else {
+        return Ok(None);
+    };
+
+    let version = Version::parse(&caps[1]).map_err(|source| Error::InvalidVersion {
+        path: path.to_path_buf(),
+        value: caps[1].to_string(),
+        source,
+    })?;
+    Ok(Some(version))
 }
@@ -0,0 +1,134 @@
+//! Checking that every recorded version of a project agrees
+//!
+//! Versions drift when one file is edited by hand or a release step is skipped.
+//! The check gathers every version the tool knows about — the project's own
+//! version locations, its lock file entries, the latest release tag and the
+//! latest changelog heading — and compares them with the project version.
+
+use crate::changelog;
+use crate::git;
+use crate::project::{Project, VersionLocation};
+use anyhow::Result;
+use semver::Version;
+use std::path::Path;
+
+/// One recorded version of the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckEntry {
+    /// What kind of record this is, e.g. `manifest` or `tag`
+    pub source: &'static str,
+    pub location: VersionLocation,
+    /// Whether the version equals the project version
+    pub matches: bool,
+}
+
+/// The versions found by [`check_versions`]
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    /// The project version, which every entry is compared against
+    pub version: Version,
+    pub entries: Vec<CheckEntry>,
+}
+
+impl CheckReport {
+    /// Whether every recorded version equals the project version
+    pub fn is_consistent(&self) -> bool {
+        self.entries.iter().all(|entry| entry.matches)
+    }
+
+    /// Format the entries as a table with a status column
+    pub fn to_table(&self) -> String {
+        let rows: Vec<[String; 4]> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                [
+                    entry.source.to_string(),
+                    format!(
+                        "{} ({})",
+                        entry.location.path.display(),
+                        entry.location.field
+                    ),
+                    entry.location.value.clone(),
+                    if entry.matches { "ok" } else { "MISMATCH" }.to_string(),
+                ]
+            })
+            .collect();
+
+        let header = ["SOURCE", "LOCATION", "VERSION", "STATUS"].map(str::to_string);
+        let mut widths = header.clone().map(|cell| cell.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut table = String::new();
+        for row in std::iter::once(&header).chain(&rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect();
+            table.push_str(cells.join("  ").trim_end());
+            table.push('\n');
+        }
+        table
+    }
+}
+
+/// Gather every recorded version of the project in `dir`
+pub fn check_versions(project: &dyn Project, dir: &Path) -> Result<CheckReport> {
+    let version = project.get_version()?;
+    let entry = |source: &'static str, location: VersionLocation| {
+        let value = location.value.strip_prefix('v').unwrap_or(&location.value);
+        let matches = Version::parse(value).is_ok_and(|v| v == version);
+        CheckEntry {
+            source,
+            location,
+            matches,
+        }
+    };
+
+    let mut entries = Vec::new();
+    for location in project.get_version_locations()? {
+        entries.push(entry("manifest", location));
+    }
+    for location in project.get_lockfile_versions()? {
+        entries.push(entry("lock file", location));
+    }
+
+    // The latest release tag, for projects whose version isn't the tag itself
+    let placeholder = project.get_tag_name(&Version::new(0, 0, 0));
+    let prefix = placeholder.strip_suffix("v0.0.0").unwrap_or("");
+    if let Some((tag, tag_version)) = git::latest_version_tag(dir, prefix) {
+        let is_version_location = entries
+            .iter()
+            .any(|entry| entry.location.field == format!("tag {tag}"));
+        if !is_version_location {
+            entries.push(entry(
+                "tag",
+                VersionLocation {
+                    path: dir.to_path_buf(),
+                    field: tag,
+                    value: tag_version.to_string(),
+                },
+            ));
+        }
+    }
+
+    if let Some(changelog_path) = changelog::find_changelog(&dir.to_string_lossy()) {
+        if let Some(changelog_version) = changelog::latest_release_version(&changelog_path)? {
+            entries.push(entry(
+                "changelog",
+                VersionLocation {
+                    path: changelog_path,
+                    field: "latest release".to_string(),
+                    value: changelog_version.to_string(),
+                },
+            ));
+        }
+    }
+
+    Ok(CheckReport { version, entries })
+}
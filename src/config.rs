@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use log::debug;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Name of the optional configuration file in the project directory
+pub const CONFIG_FILE_NAME: &str = ".project-version.toml";
+
+/// Project configuration, read from `.project-version.toml`
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Additional files that contain the version string
+    pub files: Vec<FileRule>,
+
+    /// Options for Helm chart projects
+    pub helm: HelmConfig,
+
+    /// Project types defined without code
+    pub types: Vec<ProjectTypeConfig>,
+}
+
+/// Options for Helm chart projects, from the `[helm]` section
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HelmConfig {
+    /// Also update `appVersion`: `true` keeps it equal to the chart version, or
+    /// a string sets it to that value
+    pub app_version: Option<AppVersion>,
+
+    /// Update the versions of local `file://` subchart dependencies to the new
+    /// chart version, leaving the subcharts' own versions alone
+    pub update_dependencies: bool,
+}
+
+/// How a Helm chart's `appVersion` is updated
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum AppVersion {
+    /// Keep `appVersion` in lockstep with the chart version
+    Sync(bool),
+    /// Set `appVersion` to a specific value
+    Value(String),
+}
+
+/// A file (or glob of files) whose version string is rewritten on each release
+///
+/// The `search` and `replace` templates may contain `{current_version}` and
+/// `{new_version}` placeholders.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileRule {
+    /// Path or glob pattern, relative to the project directory
+    pub path: String,
+
+    /// Text to search for
+    #[serde(default = "default_search")]
+    pub search: String,
+
+    /// Text to replace it with
+    #[serde(default = "default_replace")]
+    pub replace: String,
+}
+
+fn default_search() -> String {
+    "{current_version}".to_string()
+}
+
+fn default_replace() -> String {
+    "{new_version}".to_string()
+}
+
+/// A project type defined in the configuration, from a `[[types]]` entry
+///
+/// The version is read from `file` with either a regular expression or, for
+/// TOML, JSON and YAML files, a dotted key path such as `plugin.version`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectTypeConfig {
+    /// Name of the type, as listed by `types` and passed to `--type`
+    pub name: String,
+
+    /// Glob, relative to the project directory, of the files that identify
+    /// the type
+    pub detect: String,
+
+    /// The file holding the version, relative to the project directory.
+    /// Defaults to the first file matching `detect`.
+    pub file: Option<String>,
+
+    /// Regular expression whose `version` group, or else first group, is the
+    /// version. Only the first match is updated.
+    pub pattern: Option<String>,
+
+    /// Dotted key path of the version; numeric segments index arrays
+    pub key: Option<String>,
+
+    /// Format of the file for `key`, if its extension doesn't say
+    pub format: Option<KeyFormat>,
+
+    /// Detection priority; by default these types are tried before the
+    /// built-in types
+    #[serde(default = "default_type_priority")]
+    pub priority: i32,
+}
+
+/// The format of a file whose version is found by key path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+fn default_type_priority() -> i32 {
+    1000
+}
+
+/// Load the configuration for a project directory, or the defaults if there is
+/// no configuration file
+pub fn load_config(dir: &str) -> Result<Config> {
+    let path = Path::new(dir).join(CONFIG_FILE_NAME);
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    debug!("Loading configuration from {}", path.display());
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -0,0 +1,305 @@
+//! Project types defined in the configuration file
+//!
+//! Each `[[types]]` entry becomes a detector that recognizes the project by a
+//! glob, and a project that reads and rewrites the version in one file with a
+//! regular expression or a TOML, JSON or YAML key path.
+
+use crate::config::{KeyFormat, ProjectTypeConfig};
+use crate::diff::FileChange;
+use crate::error::{Error, Result};
+use crate::json_edit;
+use crate::project::{parse_version, Project, VersionLocation};
+use crate::registry::ProjectDetector;
+use crate::yaml_edit;
+use anyhow::{anyhow, Context};
+use regex::Regex;
+use semver::Version;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Where the version is recorded in the file
+#[derive(Debug, Clone)]
+enum VersionField {
+    Pattern(Regex),
+    Key {
+        format: KeyFormat,
+        path: Vec<String>,
+    },
+}
+
+// A step in a key path
+enum KeySegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl VersionField {
+    fn key_segments(path: &[String]) -> Vec<KeySegment<'_>> {
+        path.iter()
+            .map(|segment| match segment.parse() {
+                Ok(index) => KeySegment::Index(index),
+                Err(_) => KeySegment::Key(segment),
+            })
+            .collect()
+    }
+
+    fn json_path(path: &[String]) -> Vec<json_edit::Segment<'_>> {
+        Self::key_segments(path)
+            .into_iter()
+            .map(|segment| match segment {
+                KeySegment::Key(key) => json_edit::Segment::Key(key),
+                KeySegment::Index(index) => json_edit::Segment::Index(index),
+            })
+            .collect()
+    }
+
+    fn yaml_path(path: &[String]) -> Vec<yaml_edit::Segment<'_>> {
+        Self::key_segments(path)
+            .into_iter()
+            .map(|segment| match segment {
+                KeySegment::Key(key) => yaml_edit::Segment::Key(key),
+                KeySegment::Index(index) => yaml_edit::Segment::Index(index),
+            })
+            .collect()
+    }
+
+    // The TOML value at `path`, if there is one
+    fn toml_value<'a>(
+        document: &'a mut toml_edit::DocumentMut,
+        path: &[String],
+    ) -> Option<&'a mut toml_edit::Value> {
+        let mut item = document.as_item_mut();
+        for segment in Self::key_segments(path) {
+            item = match segment {
+                KeySegment::Key(key) => item.get_mut(key)?,
+                KeySegment::Index(index) => item.get_mut(index)?,
+            };
+        }
+        item.as_value_mut()
+    }
+
+    // The version as written in `content`, or `None` if it isn't there
+    fn read(&self, content: &str) -> anyhow::Result<Option<String>> {
+        match self {
+            VersionField::Pattern(re) => Ok(re
+                .captures(content)
+                .and_then(|caps| caps.name("version").or_else(|| caps.get(1)))
+                .map(|m| m.as_str().to_string())),
+            VersionField::Key {
+                format: KeyFormat::Json,
+                path,
+            } => Ok(json_edit::find_string(content, &Self::json_path(path))?.map(|s| s.value)),
+            VersionField::Key {
+                format: KeyFormat::Yaml,
+                path,
+            } => Ok(yaml_edit::find_scalar(content, &Self::yaml_path(path))?.map(|s| s.value)),
+            VersionField::Key {
+                format: KeyFormat::Toml,
+                path,
+            } => {
+                let mut document: toml_edit::DocumentMut =
+                    content.parse().context("Failed to parse TOML")?;
+                Ok(Self::toml_value(&mut document, path)
+                    .and_then(|value| value.as_str().map(str::to_string)))
+            }
+        }
+    }
+
+    // Replace the version in `content` with `new_value`
+    fn replace(&self, content: &str, new_value: &str) -> anyhow::Result<String> {
+        match self {
+            VersionField::Pattern(re) => {
+                let m = re
+                    .captures(content)
+                    .and_then(|caps| caps.name("version").or_else(|| caps.get(1)))
+                    .ok_or_else(|| anyhow!("Pattern '{}' not found", re.as_str()))?;
+                let mut new_content = content.to_string();
+                new_content.replace_range(m.range(), new_value);
+                Ok(new_content)
+            }
+            VersionField::Key {
+                format: KeyFormat::Json,
+                path,
+            } => json_edit::replace_string(content, &Self::json_path(path), new_value),
+            VersionField::Key {
+                format: KeyFormat::Yaml,
+                path,
+            } => yaml_edit::replace_scalar(content, &Self::yaml_path(path), new_value),
+            VersionField::Key {
+                format: KeyFormat::Toml,
+                path,
+            } => {
+                let mut document: toml_edit::DocumentMut =
+                    content.parse().context("Failed to parse TOML")?;
+                let value = Self::toml_value(&mut document, path)
+                    .ok_or_else(|| anyhow!("No {} found", path.join(".")))?;
+                // Keep the comments and spacing around the value
+                let decor = value.decor().clone();
+                *value = new_value.into();
+                *value.decor_mut() = decor;
+                Ok(document.to_string())
+            }
+        }
+    }
+
+    // Describe the field for `check` and `get --format json`
+    fn describe(&self) -> String {
+        match self {
+            VersionField::Pattern(re) => re.as_str().to_string(),
+            VersionField::Key { path, .. } => path.join("."),
+        }
+    }
+}
+
+/// A project whose type is defined in the configuration
+pub struct CustomProject {
+    name: String,
+    path: PathBuf,
+    field: VersionField,
+}
+
+impl CustomProject {
+    fn read(&self) -> Result<(String, String)> {
+        let content = fs::read_to_string(&self.path).map_err(|source| Error::Io {
+            path: self.path.clone(),
+            source,
+        })?;
+        let value = self
+            .field
+            .read(&content)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?
+            .ok_or_else(|| Error::MissingVersion {
+                path: self.path.clone(),
+            })?;
+        Ok((content, value))
+    }
+}
+
+impl Project for CustomProject {
+    fn get_project_type(&self) -> &str {
+        &self.name
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        let (_, value) = self.read()?;
+        parse_version(&self.path, value.strip_prefix('v').unwrap_or(&value))
+    }
+
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let (_, value) = self.read()?;
+        Ok(vec![VersionLocation {
+            path: self.path.clone(),
+            field: self.field.describe(),
+            value,
+        }])
+    }
+
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let (content, value) = self.read()?;
+
+        // Keep a `v` prefix
+        let prefix = if value.starts_with('v') { "v" } else { "" };
+        let new_content = self
+            .field
+            .replace(&content, &format!("{prefix}{version}"))
+            .with_context(|| format!("Failed to update {}", self.path.display()))?;
+
+        Ok(vec![FileChange::new(&self.path, content, new_content)])
+    }
+
+    fn get_file_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn get_files_to_commit(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+/// Detects a project type defined in the configuration
+pub struct CustomDetector {
+    config: ProjectTypeConfig,
+    field: VersionField,
+}
+
+impl CustomDetector {
+    /// Compile a `[[types]]` entry, checking its pattern or key
+    pub fn new(config: &ProjectTypeConfig) -> Result<Self> {
+        let name = &config.name;
+        let field = match (&config.pattern, &config.key) {
+            (Some(pattern), None) => {
+                let re = Regex::new(pattern)
+                    .with_context(|| format!("Invalid pattern for project type '{name}'"))?;
+                if re.captures_len() < 2 {
+                    return Err(anyhow!(
+                        "The pattern for project type '{name}' needs a group around the version"
+                    )
+                    .into());
+                }
+                VersionField::Pattern(re)
+            }
+            (None, Some(key)) => {
+                let file = config.file.as_deref().unwrap_or(&config.detect);
+                let extension = Path::new(file).extension().and_then(|e| e.to_str());
+                let format = match (config.format, extension) {
+                    (Some(format), _) => format,
+                    (None, Some("toml")) => KeyFormat::Toml,
+                    (None, Some("json")) => KeyFormat::Json,
+                    (None, Some("yaml" | "yml")) => KeyFormat::Yaml,
+                    _ => {
+                        return Err(
+                            anyhow!("Set the format of {file} for project type '{name}'").into(),
+                        )
+                    }
+                };
+                VersionField::Key {
+                    format,
+                    path: key.split('.').map(str::to_string).collect(),
+                }
+            }
+            _ => {
+                return Err(anyhow!("Project type '{name}' needs either a pattern or a key").into())
+            }
+        };
+
+        Ok(Self {
+            config: config.clone(),
+            field,
+        })
+    }
+}
+
+impl ProjectDetector for CustomDetector {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn priority(&self) -> i32 {
+        self.config.priority
+    }
+
+    fn description(&self) -> &str {
+        &self.config.detect
+    }
+
+    fn detect(&self, dir: &Path) -> Result<Option<Box<dyn Project>>> {
+        let pattern = dir.join(&self.config.detect);
+        let matched = glob::glob(&pattern.to_string_lossy())
+            .with_context(|| format!("Invalid detect glob: {}", self.config.detect))?
+            .filter_map(|entry| entry.ok())
+            .find(|path| path.is_file());
+        let Some(matched) = matched else {
+            return Ok(None);
+        };
+
+        let path = match &self.config.file {
+            Some(file) => dir.join(file),
+            None => matched,
+        };
+        Ok(Some(Box::new(CustomProject {
+            name: self.config.name.clone(),
+            path,
+            field: self.field.clone(),
+        })))
+    }
+}
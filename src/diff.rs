@@ -0,0 +1,97 @@
+//! Proposed file changes, previewed as unified diffs
+//!
+//! Projects and the changelog updater compute the new content of every file
+//! they change before anything is written, so that a dry run can show exactly
+//! what a release would write.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use similar::TextDiff;
+use std::fs;
+use std::path::PathBuf;
+
+/// The proposed new content of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// The file to change
+    pub path: PathBuf,
+    /// The content of the file now
+    pub original: String,
+    /// The content the file would have after the change
+    pub updated: String,
+}
+
+impl FileChange {
+    pub fn new(path: impl Into<PathBuf>, original: String, updated: String) -> Self {
+        Self {
+            path: path.into(),
+            original,
+            updated,
+        }
+    }
+
+    /// Whether the new content differs from the original
+    pub fn is_changed(&self) -> bool {
+        self.original != self.updated
+    }
+
+    /// Write the new content to the file
+    pub fn apply(&self) -> Result<()> {
+        fs::write(&self.path, &self.updated)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Render the change as a unified diff, or an empty string if the content
+    /// is unchanged
+    pub fn unified_diff(&self) -> String {
+        if !self.is_changed() {
+            return String::new();
+        }
+
+        let path = self.path.display().to_string();
+        TextDiff::from_lines(&self.original, &self.updated)
+            .unified_diff()
+            .context_radius(3)
+            .header(&path, &path)
+            .to_string()
+    }
+
+    /// Render the unified diff with removed lines in red and added lines in
+    /// green
+    pub fn colored_diff(&self) -> String {
+        self.unified_diff()
+            .lines()
+            .map(|line| {
+                if line.starts_with("---") || line.starts_with("+++") {
+                    line.bold().to_string()
+                } else if line.starts_with("@@") {
+                    line.cyan().to_string()
+                } else if line.starts_with('-') {
+                    line.red().to_string()
+                } else if line.starts_with('+') {
+                    line.green().to_string()
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Add `change` to `changes`, replacing the new content of an earlier change
+/// to the same file. `change` must have been computed from that content.
+pub fn merge(changes: &mut Vec<FileChange>, change: FileChange) {
+    match changes.iter_mut().find(|c| c.path == change.path) {
+        Some(existing) => existing.updated = change.updated,
+        None => changes.push(change),
+    }
+}
+
+/// Write every change to disk
+pub fn apply_all(changes: &[FileChange]) -> Result<()> {
+    for change in changes {
+        change.apply()?;
+    }
+    Ok(())
+}
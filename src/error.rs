@@ -0,0 +1,101 @@
+//! Errors from detecting a project, reading and writing its version, updating
+//! its changelog, and git
+//!
+//! The failures callers may want to handle are distinct variants carrying the
+//! paths, versions or tags involved; anything else is wrapped in
+//! [`Error::Other`].
+
+use semver::Version;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// No supported project file was found
+    #[error("No supported project file found in {}", dir.display())]
+    NoProject { dir: PathBuf },
+
+    /// No project type is registered under this name
+    #[error("Unknown project type '{name}'; run `project-version types` to list them")]
+    UnknownProjectType { name: String },
+
+    /// The requested project type wasn't found
+    #[error("No {name} project found in {}", dir.display())]
+    ProjectTypeNotFound { name: String, dir: PathBuf },
+
+    /// The project file doesn't record a version
+    #[error("No version field found in {}", path.display())]
+    MissingVersion { path: PathBuf },
+
+    /// The recorded version isn't a valid semantic version
+    #[error("Failed to parse version '{value}' from {}", path.display())]
+    InvalidVersion {
+        path: PathBuf,
+        value: String,
+        #[source]
+        source: semver::Error,
+    },
+
+    /// The requested version is lower than the current one
+    #[error("New version ({new}) is lower than current version ({current}). Use --force to override this check.")]
+    VersionNotIncreasing { current: Version, new: Version },
+
+    /// The release tag already exists
+    #[error("Tag {tag} already exists")]
+    TagExists { tag: String },
+
+    /// A git command failed
+    #[error("git {command} failed: {message}")]
+    Git { command: String, message: String },
+
+    /// A file couldn't be read or written
+    #[error("Failed to access {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl Error {
+    /// The innermost typed error, looking through errors that were passed
+    /// along as [`Error::Other`]
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Other(error) => error.downcast_ref::<Error>().map_or(self, Error::root),
+            _ => self,
+        }
+    }
+
+    /// The process exit code the command line reports for this error. Codes 1
+    /// (any other error) and 2 (invalid arguments) are left to their usual
+    /// meaning.
+    pub fn exit_code(&self) -> u8 {
+        match self.root() {
+            Error::UnknownProjectType { .. } => 2,
+            Error::NoProject { .. } | Error::ProjectTypeNotFound { .. } => 3,
+            Error::MissingVersion { .. } | Error::InvalidVersion { .. } => 4,
+            Error::VersionNotIncreasing { .. } => 5,
+            Error::TagExists { .. } => 6,
+            Error::Git { .. } => 7,
+            Error::Io { .. } => 8,
+            Error::Other(_) => 1,
+        }
+    }
+}
+
+/// The process exit code for an error, using the code of the first typed
+/// [`Error`] it was caused by
+pub fn exit_code(error: &anyhow::Error) -> ExitCode {
+    let code = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<Error>())
+        .map_or(1, Error::exit_code);
+    ExitCode::from(code)
+}
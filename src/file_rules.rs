@@ -0,0 +1,87 @@
+use crate::config::FileRule;
+use crate::diff::FileChange;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use semver::Version;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Substitute the version placeholders in a search or replace template
+fn render_template(template: &str, current_version: &Version, new_version: &Version) -> String {
+    template
+        .replace("{current_version}", &current_version.to_string())
+        .replace("{new_version}", &new_version.to_string())
+}
+
+// Expand a rule's path, which may be a glob, to the files it names
+fn expand_rule_path(dir: &Path, rule: &FileRule) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join(&rule.path);
+    let pattern = pattern.to_string_lossy();
+
+    let mut paths = Vec::new();
+    for entry in glob::glob(&pattern).with_context(|| format!("Invalid path: {}", rule.path))? {
+        let path = entry.with_context(|| format!("Failed to read {}", rule.path))?;
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(anyhow!("No files match configured path: {}", rule.path));
+    }
+
+    Ok(paths)
+}
+
+/// Compute the new contents of every file matched by the configured rules,
+/// without making changes. Files with `pending` changes, made earlier in the
+/// release, are updated from their changed content. Fails if any rule's search
+/// text does not occur in a file it applies to.
+pub fn compute_changes(
+    dir: &Path,
+    rules: &[FileRule],
+    current_version: &Version,
+    new_version: &Version,
+    pending: &[FileChange],
+) -> Result<Vec<FileChange>> {
+    let mut changes: Vec<FileChange> = Vec::new();
+
+    for rule in rules {
+        let search = render_template(&rule.search, current_version, new_version);
+        let replace = render_template(&rule.replace, current_version, new_version);
+
+        for path in expand_rule_path(dir, rule)? {
+            // Several rules may apply to the same file
+            let position = changes.iter().position(|change| change.path == path);
+            let content = match position {
+                Some(i) => changes[i].updated.clone(),
+                None => match pending.iter().find(|change| change.path == path) {
+                    Some(change) => change.updated.clone(),
+                    None => fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?,
+                },
+            };
+
+            let count = content.matches(&search).count();
+            if count == 0 {
+                return Err(anyhow!(
+                    "Pattern '{}' not found in {}",
+                    search,
+                    path.display()
+                ));
+            }
+            debug!(
+                "Replacing {count} occurrence(s) of '{search}' in {}",
+                path.display()
+            );
+
+            let new_content = content.replace(&search, &replace);
+            match position {
+                Some(i) => changes[i].updated = new_content,
+                None => changes.push(FileChange::new(path, content, new_content)),
+            }
+        }
+    }
+
+    Ok(changes)
+}
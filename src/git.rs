@@ -1,74 +1,94 @@
-use anyhow::{anyhow, Context, Result};
+use crate::error::{Error, Result};
 use log::{debug, warn};
-use std::path::PathBuf;
+use semver::Version;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-// Check if the current directory is a git repository
-pub fn is_git_repo() -> bool {
+// Check if `dir` is in a git repository
+pub fn is_git_repo(dir: &Path) -> bool {
     Command::new("git")
         .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(dir)
         .output()
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
 
-// Commit changes to the specified files
-pub fn commit_changes(files: &[PathBuf], message: &str) -> Result<()> {
-    if !is_git_repo() {
+// Commit changes to the specified files in the repository containing `dir`,
+// returning whether a commit was made
+pub fn commit_changes(dir: &Path, files: &[PathBuf], message: &str) -> Result<bool> {
+    if !is_git_repo(dir) {
         warn!("Not a git repository, skipping commit");
-        return Ok(());
+        return Ok(false);
     }
 
     // Add files to staging area
     for file in files {
         debug!("Staging file: {}", file.display());
 
+        // Paths are relative to the current directory, not to `dir`
+        let path = file.canonicalize().unwrap_or_else(|_| file.clone());
         let status = Command::new("git")
-            .args(["add", &file.to_string_lossy()])
+            .args(["add", &path.to_string_lossy()])
+            .current_dir(dir)
             .status()
-            .context("Failed to run git add command")?;
+            .map_err(|e| failed("add", e))?;
 
         if !status.success() {
-            return Err(anyhow!(
-                "Failed to add file to git staging area: {}",
-                file.display()
-            ));
+            return Err(failed("add", format!("could not stage {}", file.display())));
         }
     }
 
+    // Tag-only releases may have nothing to commit
+    let staged = Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(dir)
+        .status()
+        .map_err(|e| failed("diff", e))?;
+
+    if staged.success() {
+        debug!("No staged changes, skipping commit");
+        return Ok(false);
+    }
+
     // Commit changes
     debug!("Committing with message: {message}");
 
-    let status = Command::new("git")
+    // Capture git's output so it doesn't mix with ours
+    let output = Command::new("git")
         .args(["commit", "-m", message])
-        .status()
-        .context("Failed to run git commit command")?;
+        .current_dir(dir)
+        .output()
+        .map_err(|e| failed("commit", e))?;
 
-    if !status.success() {
-        return Err(anyhow!("Git commit failed"));
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(failed("commit", stderr.trim()));
     }
 
-    Ok(())
+    Ok(true)
 }
 
-// Check if a tag exists
-pub fn tag_exists(tag: &str) -> Result<bool> {
-    if !is_git_repo() {
-        warn!("Not a git repository, assuming tag doesn't exist");
+// Check if a tag exists in the repository containing `dir`
+pub fn tag_exists(dir: &Path, tag: &str) -> Result<bool> {
+    if !is_git_repo(dir) {
+        // Planning (and dry runs) check this; committing warns about it
+        debug!("Not a git repository, assuming tag doesn't exist");
         return Ok(false);
     }
 
     let output = Command::new("git")
         .args(["tag", "-l", tag])
+        .current_dir(dir)
         .output()
-        .context("Failed to run git tag command")?;
+        .map_err(|e| failed("tag", e))?;
 
     Ok(!output.stdout.is_empty())
 }
 
-// Create a tag
-pub fn create_tag(tag: &str, force: bool) -> Result<()> {
-    if !is_git_repo() {
+// Create a tag in the repository containing `dir`
+pub fn create_tag(dir: &Path, tag: &str, force: bool) -> Result<()> {
+    if !is_git_repo(dir) {
         warn!("Not a git repository, skipping tag creation");
         return Ok(());
     }
@@ -83,14 +103,155 @@ pub fn create_tag(tag: &str, force: bool) -> Result<()> {
 
     debug!("Creating git tag: {tag}");
 
-    let status = Command::new("git")
+    let output = Command::new("git")
         .args(&args)
-        .status()
-        .context("Failed to run git tag command")?;
+        .current_dir(dir)
+        .output()
+        .map_err(|e| failed("tag", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !force && stderr.contains("already exists") {
+            return Err(Error::TagExists {
+                tag: tag.to_string(),
+            });
+        }
+        return Err(failed("tag", stderr.trim()));
+    }
+
+    Ok(())
+}
+
+// Get the SHA of the HEAD commit of the repository containing `dir`
+pub fn head_sha(dir: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| failed("rev-parse", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(failed("rev-parse", stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Undo a release in the repository containing `dir`: move HEAD back to
+// `previous_head`, or remove it if the repository had no commits, and unstage
+// everything, leaving the working tree alone
+pub fn reset(dir: &Path, previous_head: Option<&str>) -> Result<()> {
+    if !is_git_repo(dir) {
+        return Ok(());
+    }
+
+    if previous_head.is_none() && head_sha(dir).is_ok() {
+        let output = Command::new("git")
+            .args(["update-ref", "-d", "HEAD"])
+            .current_dir(dir)
+            .output()
+            .map_err(|e| failed("update-ref", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(failed("update-ref", stderr.trim()));
+        }
+    }
+
+    debug!("Resetting to {}", previous_head.unwrap_or("no commits"));
+
+    let output = Command::new("git")
+        .args(["reset", "-q"])
+        .args(previous_head)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| failed("reset", e))?;
 
-    if !status.success() {
-        return Err(anyhow!("Failed to create git tag: {}", tag));
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(failed("reset", stderr.trim()));
     }
 
     Ok(())
 }
+
+// Get the messages of the commits reachable from HEAD but not from `tag`, or of
+// every commit if the tag doesn't exist
+pub fn commit_messages_since(dir: &Path, tag: &str) -> Result<Vec<String>> {
+    let tag_ref = format!("refs/tags/{tag}");
+    let has_tag = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", &tag_ref])
+        .current_dir(dir)
+        .output()
+        .is_ok_and(|output| output.status.success());
+
+    let range = if has_tag {
+        format!("{tag_ref}..HEAD")
+    } else {
+        "HEAD".to_string()
+    };
+    let output = Command::new("git")
+        .args(["log", "--format=%B%x00", &range])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| failed("log", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(failed("log", stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+// Get the root directory of the git repository containing `dir`
+pub fn repo_root(dir: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    PathBuf::from(root).canonicalize().ok()
+}
+
+// Find the highest semver tag reachable from HEAD whose name is `prefix` followed
+// by `v` and a version, e.g. `v1.2.3` or `tools/v1.2.3`
+pub fn latest_version_tag(dir: &Path, prefix: &str) -> Option<(String, Version)> {
+    let output = Command::new("git")
+        .args(["tag", "--merged", "HEAD", "--list", &format!("{prefix}v*")])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|tag| {
+            let version = tag.strip_prefix(prefix)?.strip_prefix('v')?;
+            Some((tag.to_string(), Version::parse(version).ok()?))
+        })
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+// A git command that couldn't be run or exited with an error
+fn failed(command: &str, message: impl ToString) -> Error {
+    Error::Git {
+        command: command.to_string(),
+        message: message.to_string(),
+    }
+}
@@ -0,0 +1,301 @@
+//! Formatting-preserving edits of string values in JSON documents
+//!
+//! Manifests are parsed into a tree that records the position of every value,
+//! so an edit replaces exactly one string and leaves the rest of the file
+//! byte-for-byte unchanged. The parser also accepts the JSONC/JSON5 extensions
+//! found in hand-written manifests: comments, trailing commas, single-quoted
+//! strings and unquoted keys.
+
+use anyhow::{anyhow, Result};
+use std::ops::Range;
+
+/// A step in the path from the document root to a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// A member of an object
+    Key(&'a str),
+    /// An element of an array
+    Index(usize),
+}
+
+/// The location of a string value in a JSON document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringSpan {
+    /// Byte range of the string contents, excluding the quotes
+    pub range: Range<usize>,
+    /// The quote character used
+    pub quote: char,
+    /// The string, with escapes resolved
+    pub value: String,
+}
+
+#[derive(Debug)]
+enum Node {
+    Object(Vec<(String, Node)>),
+    Array(Vec<Node>),
+    String(StringSpan),
+    Other,
+}
+
+/// Find the string value at `path`, or `None` if there is no string there
+pub fn find_string(content: &str, path: &[Segment]) -> Result<Option<StringSpan>> {
+    let root = Parser::new(content).parse_document()?;
+
+    let mut node = &root;
+    for segment in path {
+        let next = match (segment, node) {
+            // Later duplicate keys override earlier ones, as in JSON.parse
+            (Segment::Key(key), Node::Object(members)) => members
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            (Segment::Index(index), Node::Array(elements)) => elements.get(*index),
+            _ => None,
+        };
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        node = next;
+    }
+
+    match node {
+        Node::String(span) => Ok(Some(span.clone())),
+        _ => Ok(None),
+    }
+}
+
+/// Replace the string value at `path`, keeping its quote style and everything
+/// else in the document
+pub fn replace_string(content: &str, path: &[Segment], new_value: &str) -> Result<String> {
+    let span = find_string(content, path)?
+        .ok_or_else(|| anyhow!("No string value found at {}", describe_path(path)))?;
+
+    let mut escaped = String::new();
+    for c in new_value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c == span.quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    let mut new_content = content.to_string();
+    new_content.replace_range(span.range, &escaped);
+    Ok(new_content)
+}
+
+/// Format a path for messages, e.g. `packages[""].version`
+pub fn describe_path(path: &[Segment]) -> String {
+    let mut description = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if is_identifier(key) => {
+                if !description.is_empty() {
+                    description.push('.');
+                }
+                description.push_str(key);
+            }
+            Segment::Key(key) => description.push_str(&format!("[{key:?}]")),
+            Segment::Index(index) => description.push_str(&format!("[{index}]")),
+        }
+    }
+    description
+}
+
+fn is_identifier(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+struct Parser<'a> {
+    content: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(content: &'a str) -> Self {
+        Self { content, pos: 0 }
+    }
+
+    fn parse_document(&mut self) -> Result<Node> {
+        // Skip a byte order mark
+        if self.content.starts_with('\u{feff}') {
+            self.pos = '\u{feff}'.len_utf8();
+        }
+
+        let node = self.parse_value()?;
+        self.skip_trivia()?;
+        if self.pos < self.content.len() {
+            return Err(self.error("end of document"));
+        }
+        Ok(node)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.content[self.pos..].chars().next()
+    }
+
+    fn error(&self, expected: &str) -> anyhow::Error {
+        let line = self.content[..self.pos].matches('\n').count() + 1;
+        anyhow!("Invalid JSON at line {line}: expected {expected}")
+    }
+
+    // Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            let rest = &self.content[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                let end = comment
+                    .find("*/")
+                    .ok_or_else(|| self.error("end of comment"))?;
+                self.pos += 2 + end + 2;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Node> {
+        self.skip_trivia()?;
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some(quote @ ('"' | '\'')) => Ok(Node::String(self.parse_string(quote)?)),
+            Some(_) => {
+                // Numbers, booleans and null; their values aren't needed
+                let rest = &self.content[self.pos..];
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || matches!(c, ',' | ']' | '}' | '/'))
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(self.error("a value"));
+                }
+                self.pos += len;
+                Ok(Node::Other)
+            }
+            None => Err(self.error("a value")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node> {
+        self.pos += 1;
+        let mut members = Vec::new();
+
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Node::Object(members));
+            }
+
+            let key = match self.peek() {
+                Some(quote @ ('"' | '\'')) => self.parse_string(quote)?.value,
+                _ => self.parse_identifier()?,
+            };
+
+            self.skip_trivia()?;
+            if self.peek() != Some(':') {
+                return Err(self.error("':'"));
+            }
+            self.pos += 1;
+
+            let value = self.parse_value()?;
+            members.push((key, value));
+
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err(self.error("',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Node> {
+        self.pos += 1;
+        let mut elements = Vec::new();
+
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Node::Array(elements));
+            }
+
+            elements.push(self.parse_value()?);
+
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("',' or ']'")),
+            }
+        }
+    }
+
+    // An unquoted JSON5 object key
+    fn parse_identifier(&mut self) -> Result<String> {
+        let rest = &self.content[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("an object key"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    fn parse_string(&mut self, quote: char) -> Result<StringSpan> {
+        let start = self.pos + 1;
+        let mut value = String::new();
+        let mut chars = self.content[start..].char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                self.pos = start + i + 1;
+                return Ok(StringSpan {
+                    range: start..start + i,
+                    quote,
+                    value,
+                });
+            }
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+
+            let Some((_, escape)) = chars.next() else {
+                break;
+            };
+            match escape {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => value.push('\r'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                    let code = u32::from_str_radix(&hex, 16)
+                        .map_err(|_| self.error("a unicode escape"))?;
+                    value.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                }
+                c => value.push(c),
+            }
+        }
+
+        Err(self.error("end of string"))
+    }
+}
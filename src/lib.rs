@@ -9,11 +9,15 @@
 //!
 //! ## Supported Project Types
 //!
-//! - Node.js (package.json)
-//! - Python (pyproject.toml)
+//! - Node.js (package.json, package.json5)
+//! - Python (pyproject.toml, setup.cfg, setup.py)
 //! - Rust (Cargo.toml)
-//! - Go (version.go files)
+//! - Go (version.go files or git tags)
 //! - Ruby (Gemfile, gemspec, version.rb)
+//! - Elixir (mix.exs)
+//! - Xcode (project.pbxproj, Info.plist)
+//! - Plain version files (VERSION, version.txt)
+//! - Any git repository without a manifest (versioned by its tags)
 //!
 //! ## Usage
 //!
@@ -27,6 +31,9 @@
 //! # Bump minor version
 //! project-version bump minor
 //!
+//! # Print the next minor version without changing anything
+//! project-version next minor
+//!
 //! # Bump major version with verbose output
 //! project-version bump major --verbose
 //!
@@ -48,13 +55,46 @@
 //! project-version /path/to/project bump
 //! ```
 //!
+//! ## Library Usage
+//!
+//! [`release::Releaser`] runs the same release as the `bump` and `set`
+//! commands, reporting progress to an observer instead of printing, and
+//! never prompting:
+//!
+//! ```no_run
+//! use project_version::bump::BumpType;
+//! use project_version::release::Releaser;
+//!
+//! let report = Releaser::new("path/to/project")
+//!     .bump(BumpType::Minor)
+//!     .dry_run(true)
+//!     .run()?;
+//! println!("{} → {}", report.old_version, report.new_version);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
 //! ## Options
 //!
-//! - `--dry-run` - Show what would happen without making changes
+//! - `--dry-run` - Show a unified diff of what would change without making changes
 //! - `--verbose` - Show more detailed output
 //! - `--no-commit` - Don't create a git commit
+//! - `--offline` - Update the project's own lock file entry without running the package manager
 //! - `--force` - Force setting version even if it's lower than current version
+//! - `--type <NAME>` - Use this project type instead of detecting it (`project-version types` lists them)
 
+pub mod bump;
 pub mod changelog;
+pub mod check;
+pub mod config;
+pub mod custom;
+pub mod diff;
+pub mod error;
+pub mod file_rules;
 pub mod git;
+pub mod json_edit;
+pub mod lockfile;
 pub mod project;
+pub mod registry;
+pub mod release;
+pub mod snapshot;
+pub mod yaml_edit;
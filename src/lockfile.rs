@@ -0,0 +1,282 @@
+//! Offline updates of a project's own entry in its lock files
+//!
+//! Package managers rewrite lock files by resolving dependencies, which needs
+//! network access and can upgrade unrelated packages. These functions instead
+//! change only the version recorded for the project itself, leaving every other
+//! entry byte-for-byte unchanged. Each returns the updated content, or `None` if
+//! the lock file has no entry for the project or it is already up to date.
+
+use crate::diff::FileChange;
+use crate::json_edit;
+use anyhow::{Context, Result};
+use semver::Version;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Content hashes of lock files, taken before running a package manager to
+/// find which lock files it changed
+#[derive(Debug, Clone)]
+pub struct LockfileHashes {
+    // `None` for files that don't exist
+    hashes: Vec<(PathBuf, Option<u64>)>,
+}
+
+impl LockfileHashes {
+    /// Hash the current contents of `paths`
+    pub fn capture(paths: &[PathBuf]) -> Self {
+        let hashes = paths
+            .iter()
+            .map(|path| (path.clone(), hash_file(path)))
+            .collect();
+        Self { hashes }
+    }
+
+    /// The lock files that were created or whose contents changed since the
+    /// hashes were taken
+    pub fn changed(&self) -> Vec<PathBuf> {
+        self.hashes
+            .iter()
+            .filter(|(path, before)| path.exists() && hash_file(path) != *before)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    let content = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Apply `update` to the lock file at `path`, if it exists. Returns the
+/// proposed change, or `None` if the file would be unchanged.
+pub fn rewrite(
+    path: &Path,
+    update: impl FnOnce(&str) -> Result<Option<String>>,
+) -> Result<Option<FileChange>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let new_content = update(&content)
+        .with_context(|| format!("Failed to update {}", path.display()))?
+        .filter(|new_content| *new_content != content);
+
+    Ok(new_content.map(|new_content| FileChange::new(path, content, new_content)))
+}
+
+/// Update the workspace packages named `names` in a Cargo.lock. Packages with
+/// a `source` come from a registry or git, and are never changed.
+pub fn update_cargo_lock(
+    content: &str,
+    names: &[String],
+    version: &Version,
+) -> Result<Option<String>> {
+    update_toml_packages(content, version, |package| {
+        is_cargo_workspace_package(package, names)
+    })
+}
+
+/// Read the versions of the workspace packages named `names` in a Cargo.lock,
+/// as package name and version pairs
+pub fn read_cargo_lock(content: &str, names: &[String]) -> Result<Vec<(String, String)>> {
+    read_toml_packages(content, |package| {
+        is_cargo_workspace_package(package, names)
+    })
+}
+
+// Where a package-lock.json records the project's own version
+const PACKAGE_LOCK_VERSION_PATHS: [&[json_edit::Segment]; 2] = [
+    &[json_edit::Segment::Key("version")],
+    &[
+        json_edit::Segment::Key("packages"),
+        json_edit::Segment::Key(""),
+        json_edit::Segment::Key("version"),
+    ],
+];
+
+/// Update the root `version` and `packages[""].version` of a package-lock.json
+pub fn update_package_lock(content: &str, version: &Version) -> Result<Option<String>> {
+    let mut new_content = content.to_string();
+    for path in PACKAGE_LOCK_VERSION_PATHS {
+        if json_edit::find_string(&new_content, path)?.is_some() {
+            new_content = json_edit::replace_string(&new_content, path, &version.to_string())?;
+        }
+    }
+
+    Ok((new_content != content).then_some(new_content))
+}
+
+/// Read the root `version` and `packages[""].version` of a package-lock.json,
+/// as path and version pairs
+pub fn read_package_lock(content: &str) -> Result<Vec<(String, String)>> {
+    let mut versions = Vec::new();
+    for path in PACKAGE_LOCK_VERSION_PATHS {
+        if let Some(span) = json_edit::find_string(content, path)? {
+            versions.push((json_edit::describe_path(path), span.value));
+        }
+    }
+    Ok(versions)
+}
+
+/// Update the project's own package in a uv.lock, which uv records with an
+/// editable or virtual source at the project root
+pub fn update_uv_lock(content: &str, name: &str, version: &Version) -> Result<Option<String>> {
+    update_toml_packages(content, version, |package| is_uv_root(package, name))
+}
+
+/// Read the version of the project's own package in a uv.lock
+pub fn read_uv_lock(content: &str, name: &str) -> Result<Vec<(String, String)>> {
+    read_toml_packages(content, |package| is_uv_root(package, name))
+}
+
+/// Update the project's own package in a poetry.lock, where it appears as a
+/// directory dependency on the project root
+pub fn update_poetry_lock(content: &str, name: &str, version: &Version) -> Result<Option<String>> {
+    update_toml_packages(content, version, |package| is_poetry_root(package, name))
+}
+
+/// Read the version of the project's own package in a poetry.lock
+pub fn read_poetry_lock(content: &str, name: &str) -> Result<Vec<(String, String)>> {
+    read_toml_packages(content, |package| is_poetry_root(package, name))
+}
+
+/// Update the gem's own entry under the `PATH` section of a Gemfile.lock,
+/// e.g. `    mygem (1.2.3)`
+pub fn update_gemfile_lock(content: &str, name: &str, version: &Version) -> Option<String> {
+    let span = find_gemfile_lock_entry(content, name)?;
+    let mut new_content = content.to_string();
+    new_content.replace_range(span, &version.to_string());
+    Some(new_content)
+}
+
+/// Read the version of the gem's own entry in a Gemfile.lock
+pub fn read_gemfile_lock(content: &str, name: &str) -> Option<String> {
+    find_gemfile_lock_entry(content, name).map(|span| content[span].to_string())
+}
+
+// Find the byte range of the version in the gem's own Gemfile.lock entry
+fn find_gemfile_lock_entry(content: &str, name: &str) -> Option<std::ops::Range<usize>> {
+    let entry_re = regex::Regex::new(&format!(
+        r"^(    {} \()([^)-]+)((?:-[^)]*)?\))$",
+        regex::escape(name)
+    ))
+    .unwrap();
+
+    // Only entries under a PATH section refer to the gem itself
+    let mut offset = 0;
+    let mut in_path = false;
+    for line in content.split_inclusive('\n') {
+        if !line.starts_with(' ') {
+            in_path = line.trim_end() == "PATH";
+        } else if in_path {
+            if let Some(caps) = entry_re.captures(line.trim_end_matches(['\n', '\r'])) {
+                let value = caps.get(2).unwrap();
+                return Some(offset + value.start()..offset + value.end());
+            }
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+// Workspace packages have no `source`; packages from a registry or git do
+fn is_cargo_workspace_package(package: &toml_edit::Table, names: &[String]) -> bool {
+    package.get("source").is_none()
+        && package
+            .get("name")
+            .and_then(|name| name.as_str())
+            .is_some_and(|name| names.iter().any(|n| n == name))
+}
+
+fn is_uv_root(package: &toml_edit::Table, name: &str) -> bool {
+    let is_root = package.get("source").is_some_and(|source| {
+        ["editable", "virtual", "directory"]
+            .iter()
+            .any(|kind| source.get(kind).and_then(|p| p.as_str()) == Some("."))
+    });
+    is_root && package_name_matches(package, name)
+}
+
+fn is_poetry_root(package: &toml_edit::Table, name: &str) -> bool {
+    let is_root = package.get("source").is_some_and(|source| {
+        source.get("type").and_then(|t| t.as_str()) == Some("directory")
+            && source.get("url").and_then(|u| u.as_str()) == Some(".")
+    });
+    is_root && package_name_matches(package, name)
+}
+
+// Python package names compare case-insensitively, with runs of `-`, `_` and
+// `.` treated as equal (PEP 503)
+fn package_name_matches(package: &toml_edit::Table, name: &str) -> bool {
+    let normalize = |name: &str| {
+        name.to_lowercase()
+            .split(['-', '_', '.'])
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    package
+        .get("name")
+        .and_then(|n| n.as_str())
+        .is_some_and(|n| normalize(n) == normalize(name))
+}
+
+// Set the version of every `[[package]]` table selected by `is_own`, keeping
+// the formatting of the rest of the file
+fn update_toml_packages(
+    content: &str,
+    version: &Version,
+    is_own: impl Fn(&toml_edit::Table) -> bool,
+) -> Result<Option<String>> {
+    let mut doc = content
+        .parse::<toml_edit::DocumentMut>()
+        .context("Failed to parse lock file")?;
+    let Some(packages) = doc
+        .get_mut("package")
+        .and_then(|p| p.as_array_of_tables_mut())
+    else {
+        return Ok(None);
+    };
+
+    let mut changed = false;
+    for package in packages.iter_mut().filter(|package| is_own(package)) {
+        if let Some(value) = package.get_mut("version").and_then(|v| v.as_value_mut()) {
+            let decor = value.decor().clone();
+            *value = version.to_string().into();
+            *value.decor_mut() = decor;
+            changed = true;
+        }
+    }
+
+    Ok(changed.then(|| doc.to_string()))
+}
+
+// Read the name and version of every `[[package]]` table selected by `is_own`
+fn read_toml_packages(
+    content: &str,
+    is_own: impl Fn(&toml_edit::Table) -> bool,
+) -> Result<Vec<(String, String)>> {
+    let doc = content
+        .parse::<toml_edit::DocumentMut>()
+        .context("Failed to parse lock file")?;
+    let Some(packages) = doc.get("package").and_then(|p| p.as_array_of_tables()) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(packages
+        .iter()
+        .filter(|package| is_own(package))
+        .filter_map(|package| {
+            let name = package.get("name")?.as_str()?;
+            let version = package.get("version")?.as_str()?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect())
+}
@@ -1,18 +1,42 @@
-mod changelog;
-mod git;
-mod project;
-
 use anyhow::{anyhow, Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use log::debug;
-use std::path::Path;
+use project_version::bump::{self, BumpType};
+use project_version::error::Error;
+use project_version::registry::ProjectRegistry;
+use project_version::release::{
+    LockfileUpdate, ReleaseEvent, ReleasePlan, ReleaseReport, Releaser,
+};
+use project_version::{check, config, project};
+use serde::Serialize;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+// Print a progress message, unless a JSON report was requested instead
+macro_rules! say {
+    ($config:expr, $($arg:tt)*) => {
+        if $config.output == OutputFormat::Text {
+            println!($($arg)*);
+        }
+    };
+}
 
-#[derive(Debug, Copy, Clone, ValueEnum)]
-enum BumpType {
-    Major,
-    Minor,
-    Patch,
+/// The project version, printed by `get --format json`
+#[derive(Debug, Serialize)]
+struct VersionReport<'a> {
+    #[serde(rename = "type")]
+    project_type: &'a str,
+    manifest: &'a Path,
+    version: String,
+    locations: Vec<project::VersionLocation>,
 }
 
 /// Configuration for version operations
@@ -23,8 +47,11 @@ struct VersionConfig {
     no_commit: bool,
     no_tag: bool,
     no_lockupdate: bool,
+    offline: bool,
     force_tag: bool,
+    output: OutputFormat,
     directory: String,
+    files: Vec<config::FileRule>,
 }
 
 /// Configuration specific to the set version operation
@@ -54,13 +81,40 @@ struct Cli {
     #[arg(default_value = ".", global = true)]
     directory: String,
 
+    /// Output format for bump and set; json prints a report of the release
+    /// instead of progress messages
+    #[arg(long, value_enum, default_value = "text", global = true)]
+    output: OutputFormat,
+
+    /// Treat the project as this type instead of detecting it (see `types`)
+    #[arg(long = "type", value_name = "NAME", global = true)]
+    project_type: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    /// Bump project version (major, minor, or patch)
+    /// Print the project version
+    Get {
+        /// Output format; json includes the project type, manifest path and
+        /// every place the version is recorded
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Check that every recorded version of the project agrees, exiting with
+    /// an error if they don't
+    Check,
+    /// List the project types, in the order they are detected
+    Types,
+    /// Print the version a bump would produce, without changing anything
+    Next {
+        /// Type of version bump to compute
+        #[arg(value_enum)]
+        bump_type: BumpType,
+    },
+    /// Bump project version (major, minor, patch, prerelease, or auto)
     Bump {
         /// Type of version bump to perform
         #[arg(value_enum, default_value = "patch")]
@@ -78,6 +132,11 @@ enum Commands {
         #[arg(long)]
         no_lockupdate: bool,
 
+        /// Update this project's own entry in its lock files directly, instead of
+        /// running the package manager
+        #[arg(long, conflicts_with = "no_lockupdate")]
+        offline: bool,
+
         /// Force tag creation (overwrite existing tag)
         #[arg(long)]
         force_tag: bool,
@@ -99,6 +158,11 @@ enum Commands {
         #[arg(long)]
         no_lockupdate: bool,
 
+        /// Update this project's own entry in its lock files directly, instead of
+        /// running the package manager
+        #[arg(long, conflicts_with = "no_lockupdate")]
+        offline: bool,
+
         /// Force tag creation (overwrite existing tag)
         #[arg(long)]
         force_tag: bool,
@@ -109,14 +173,31 @@ enum Commands {
     },
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            project_version::error::exit_code(&error)
+        }
+    }
+}
+
+fn run() -> Result<()> {
     // Setup logging
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     // Parse CLI arguments
     let args = Cli::parse();
 
-    if args.verbose {
+    // Only the requested output is printed for `get` and JSON reports
+    let quiet = args.output == OutputFormat::Json
+        || matches!(
+            args.command,
+            Some(Commands::Get { .. } | Commands::Next { .. } | Commands::Types)
+        );
+
+    if args.verbose && !quiet {
         println!(
             "{}",
             "project-version - Cross-language version bumper"
@@ -126,25 +207,65 @@ fn main() -> Result<()> {
         debug!("Arguments: {args:?}");
     }
 
-    if args.dry_run {
+    if args.dry_run && !quiet {
         println!("{}", "[DRY RUN] No files will be modified".yellow());
     }
 
+    // Load the optional project configuration
+    let project_config = config::load_config(&args.directory)?;
+
+    let mut registry = ProjectRegistry::default();
+    registry.register_config_types(&project_config.types)?;
+    if let Some(Commands::Types) = args.command {
+        print_types(&registry);
+        return Ok(());
+    }
+
     // Find the project file
-    let project =
-        project::detect_project(&args.directory).context("Failed to detect project type")?;
+    let dir = Path::new(&args.directory);
+    let project = match &args.project_type {
+        Some(name) => registry.detect_as(dir, name)?,
+        None => registry
+            .detect(dir)
+            .context("Failed to detect project type")?,
+    };
 
     // Get current version
     let current_version = project.get_version()?;
-    if args.verbose {
+    if args.verbose && !quiet {
         println!("Current version: {current_version}");
     }
 
     match &args.command {
+        Some(Commands::Get { format }) => {
+            print_version(project.as_ref(), &current_version, *format)?
+        }
+        Some(Commands::Types) => unreachable!("handled before detecting the project"),
+        Some(Commands::Check) => {
+            let report = check::check_versions(project.as_ref(), Path::new(&args.directory))?;
+            print!("{}", report.to_table());
+            if !report.is_consistent() {
+                return Err(anyhow!(
+                    "Recorded versions disagree with the project version {}",
+                    report.version
+                ));
+            }
+            println!("{}", "All versions agree".green());
+        }
+        Some(Commands::Next { bump_type }) => {
+            let next_version = bump::next_version(
+                project.as_ref(),
+                Path::new(&args.directory),
+                &current_version,
+                *bump_type,
+            )?;
+            println!("{next_version}");
+        }
         Some(Commands::Bump {
             bump_type,
             no_commit,
             no_lockupdate,
+            offline,
             no_tag,
             force_tag,
         }) => {
@@ -154,17 +275,21 @@ fn main() -> Result<()> {
                 verbose: args.verbose,
                 no_commit: *no_commit,
                 no_lockupdate: *no_lockupdate,
+                offline: *offline,
                 no_tag: *no_tag,
                 force_tag: *force_tag,
+                output: args.output,
                 directory: args.directory.clone(),
+                files: project_config.files.clone(),
             };
-            bump_version(project.as_ref(), current_version, *bump_type, config)?
+            bump_version(project.as_ref(), *bump_type, config)?
         }
         Some(Commands::Set {
             version,
             no_commit,
             no_tag,
             no_lockupdate,
+            offline,
             force_tag,
             force,
         }) => {
@@ -175,19 +300,23 @@ fn main() -> Result<()> {
                     verbose: args.verbose,
                     no_commit: *no_commit,
                     no_lockupdate: *no_lockupdate,
+                    offline: *offline,
                     no_tag: *no_tag,
                     force_tag: *force_tag,
+                    output: args.output,
                     directory: args.directory.clone(),
+                    files: project_config.files.clone(),
                 },
                 force: *force,
             };
-            set_version(project.as_ref(), current_version, version, config)?
+            set_version(project.as_ref(), version, config)?
         }
         None => {
             // If no subcommand is provided, just display current version
             println!("Current version: {}", current_version.to_string().blue());
             println!("\nUse 'project-version bump' to bump the version");
             println!("Use 'project-version set <VERSION>' to set a specific version");
+            println!("Use 'project-version get' to print just the version");
             println!("Run 'project-version --help' to see available commands");
         }
     }
@@ -197,160 +326,24 @@ fn main() -> Result<()> {
 
 fn bump_version(
     project: &dyn project::Project,
-    current_version: semver::Version,
     bump_type: BumpType,
     config: VersionConfig,
 ) -> Result<()> {
-    // Calculate new version
-    let new_version = match bump_type {
-        BumpType::Major => semver::Version::new(current_version.major + 1, 0, 0),
-        BumpType::Minor => {
-            semver::Version::new(current_version.major, current_version.minor + 1, 0)
-        }
-        BumpType::Patch => semver::Version::new(
-            current_version.major,
-            current_version.minor,
-            current_version.patch + 1,
-        ),
-    };
+    let releaser = releaser(&config).bump(bump_type);
+    let plan = releaser.plan_for(project)?;
 
-    println!(
+    say!(
+        config,
         "Bumping version: {} → {}",
-        current_version.to_string().blue(),
-        new_version.to_string().green().bold()
+        plan.old_version.to_string().blue(),
+        plan.new_version.to_string().green().bold()
     );
 
-    // Update project file
-    if config.dry_run {
-        let diff = project.dry_run_update(&new_version)?;
-        println!("{} {}", "[DRY RUN]".yellow(), diff);
-    } else {
-        project.update_version(&new_version)?;
-        if config.verbose {
-            println!(
-                "Updated {} with new version",
-                project.get_file_path().display()
-            );
-        }
-    }
-
-    // Check for CHANGELOG
-    let changelog_path = changelog::find_changelog(&config.directory);
-    let has_changelog = changelog_path.is_some();
-
-    if let Some(ref changelog_path) = changelog_path {
-        if config.verbose {
-            println!("Found changelog at {}", changelog_path.display());
-        }
-
-        if config.dry_run {
-            let diff = changelog::dry_run_update_changelog(changelog_path, &new_version)?;
-            println!("{} {}", "[DRY RUN]".yellow(), diff);
-        } else {
-            changelog::update_changelog(changelog_path, &new_version)?;
-            println!("Updated changelog: {}", changelog_path.display());
-        }
-    }
-
-    // Update lock files with the appropriate package manager
-    if !config.dry_run && !config.no_lockupdate {
-        if let Some(update_command) = project.get_package_manager_update_command() {
-            println!("Updating dependencies with: {}", update_command.cyan());
-
-            let output = std::process::Command::new("sh")
-                .arg("-c")
-                .arg(&update_command)
-                .current_dir(project.get_file_path().parent().unwrap_or(Path::new(".")))
-                .output();
-
-            match output {
-                Ok(output) => {
-                    if output.status.success() {
-                        if config.verbose {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            if !stdout.is_empty() {
-                                println!("Package manager output:\n{stdout}");
-                            }
-                        }
-                        println!("Successfully updated dependencies");
-                    } else {
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        eprintln!("Failed to update dependencies: {stderr}");
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to run package manager: {e}");
-                }
-            }
-        }
-    } else if let Some(update_command) = project.get_package_manager_update_command() {
-        println!(
-            "{} Would update dependencies with: {}",
-            if config.no_lockupdate {
-                "[NO UPDATE]".white()
-            } else {
-                "[DRY RUN]".yellow()
-            },
-            update_command
-        );
-    }
-
-    // Git operations
-    if !config.no_commit && !config.dry_run {
-        let files_to_commit = project.get_files_to_commit();
-        if has_changelog {
-            let mut files = files_to_commit;
-            // Safe to unwrap since we checked has_changelog
-            files.push(changelog_path.unwrap());
-            git::commit_changes(&files, &format!("release: version {new_version}"))?;
-        } else {
-            git::commit_changes(&files_to_commit, &format!("release: version {new_version}"))?;
-        }
-        println!("Committed version bump");
-
-        if !config.no_tag {
-            let tag_name = format!("v{new_version}");
-
-            // Check if tag exists
-            if git::tag_exists(&tag_name)? {
-                if config.force_tag {
-                    git::create_tag(&tag_name, true)?;
-                    println!("Forced creation of tag: {}", tag_name.green());
-                } else {
-                    // Prompt user to overwrite
-                    use dialoguer::{theme::ColorfulTheme, Confirm};
-
-                    let overwrite = Confirm::with_theme(&ColorfulTheme::default())
-                        .with_prompt(format!("Tag {tag_name} already exists. Overwrite?"))
-                        .default(false)
-                        .interact()?;
-
-                    if overwrite {
-                        git::create_tag(&tag_name, true)?;
-                        println!("Overwrote existing tag: {}", tag_name.green());
-                    } else {
-                        println!("Skipped tag creation (tag already exists)");
-                    }
-                }
-            } else {
-                git::create_tag(&tag_name, false)?;
-                println!("Created tag: {}", tag_name.green());
-            }
-        }
-    } else if config.dry_run {
-        println!(
-            "{} Would commit changes and create tag v{}",
-            "[DRY RUN]".yellow(),
-            new_version
-        );
-    }
-
-    Ok(())
+    release(project, plan, &config)
 }
 
 fn set_version(
     project: &dyn project::Project,
-    current_version: semver::Version,
     version_str: &str,
     config: SetVersionConfig,
 ) -> Result<()> {
@@ -360,138 +353,214 @@ fn set_version(
     let new_version = semver::Version::parse(clean_version_str)
         .context(format!("Invalid version format: {version_str}"))?;
 
-    // Check if the new version is lower than the current version
-    if !config.force && new_version < current_version {
-        return Err(anyhow!("New version ({}) is lower than current version ({}). Use --force to override this check.",
-            new_version, current_version));
-    }
+    let releaser = releaser(&config.config)
+        .set(new_version)
+        .force(config.force);
+    let plan = releaser.plan_for(project)?;
 
-    println!(
+    say!(
+        config.config,
         "Setting version: {} → {}",
-        current_version.to_string().blue(),
-        new_version.to_string().green().bold()
+        plan.old_version.to_string().blue(),
+        plan.new_version.to_string().green().bold()
     );
 
-    // Update project file
-    if config.config.dry_run {
-        let diff = project.dry_run_update(&new_version)?;
-        println!("{} {}", "[DRY RUN]".yellow(), diff);
-    } else {
-        project.update_version(&new_version)?;
-        if config.config.verbose {
-            println!(
-                "Updated {} with new version",
-                project.get_file_path().display()
-            );
+    release(project, plan, &config.config)
+}
+
+// A releaser configured from the command line options
+fn releaser(config: &VersionConfig) -> Releaser {
+    Releaser::new(&config.directory)
+        .dry_run(config.dry_run)
+        .commit(!config.no_commit)
+        .tag(!config.no_tag)
+        .force_tag(config.force_tag)
+        .update_lockfiles(!config.no_lockupdate)
+        .offline(config.offline)
+        .files(config.files.clone())
+}
+
+// Show the release plan (in a dry run) or carry it out
+fn release(
+    project: &dyn project::Project,
+    mut plan: ReleasePlan,
+    config: &VersionConfig,
+) -> Result<()> {
+    if config.verbose {
+        if let Some(changelog_path) = &plan.changelog {
+            say!(config, "Found changelog at {}", changelog_path.display());
         }
     }
 
-    // Check for CHANGELOG
-    let changelog_path = changelog::find_changelog(&config.config.directory);
-    let has_changelog = changelog_path.is_some();
-
-    if let Some(ref changelog_path) = changelog_path {
-        if config.config.verbose {
-            println!("Found changelog at {}", changelog_path.display());
+    if config.dry_run {
+        print_plan(config, project, &plan);
+        if config.output == OutputFormat::Json {
+            let report = ReleaseReport::dry_run(&plan);
+            println!("{}", serde_json::to_string_pretty(&report)?);
         }
+        return Ok(());
+    }
 
-        if config.config.dry_run {
-            let diff = changelog::dry_run_update_changelog(changelog_path, &new_version)?;
-            println!("{} {}", "[DRY RUN]".yellow(), diff);
-        } else {
-            changelog::update_changelog(changelog_path, &new_version)?;
-            println!("Updated changelog: {}", changelog_path.display());
+    // Ask before replacing an existing tag, unless --force-tag was given
+    if let Some(tag) = plan.tag.as_mut().filter(|tag| tag.exists && !tag.force) {
+        use dialoguer::{theme::ColorfulTheme, Confirm};
+
+        // Scripts can't answer the prompt
+        if !io::stdin().is_terminal() {
+            return Err(Error::TagExists {
+                tag: tag.name.clone(),
+            }
+            .into());
         }
+
+        tag.force = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Tag {} already exists. Overwrite?", tag.name))
+            .default(false)
+            .interact()?;
     }
 
-    // Update lock files with the appropriate package manager
-    if !config.config.dry_run {
-        if let Some(update_command) = project.get_package_manager_update_command() {
-            println!("Updating dependencies with: {}", update_command.cyan());
-
-            let output = std::process::Command::new("sh")
-                .arg("-c")
-                .arg(&update_command)
-                .current_dir(project.get_file_path().parent().unwrap_or(Path::new(".")))
-                .output();
-
-            match output {
-                Ok(output) => {
-                    if output.status.success() {
-                        if config.config.verbose {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            if !stdout.is_empty() {
-                                println!("Package manager output:\n{stdout}");
-                            }
-                        }
-                        println!("Successfully updated dependencies");
-                    } else {
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        eprintln!("Failed to update dependencies: {stderr}");
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to run package manager: {e}");
-                }
+    let lockfiles: Vec<PathBuf> = plan
+        .lockfile_changes()
+        .map(|change| change.path.clone())
+        .collect();
+    let mut observer = |event: &ReleaseEvent| match event {
+        ReleaseEvent::FileUpdated(path) if plan.changelog.as_ref() == Some(path) => {
+            say!(config, "Updated changelog: {}", path.display())
+        }
+        ReleaseEvent::FileUpdated(path) if lockfiles.contains(path) => {
+            say!(config, "Updated lock file: {}", path.display())
+        }
+        ReleaseEvent::FileUpdated(path) => {
+            if config.verbose {
+                say!(config, "Updated {} with new version", path.display());
             }
         }
-    } else if let Some(update_command) = project.get_package_manager_update_command() {
-        println!(
-            "{} Would update dependencies with: {}",
+        ReleaseEvent::UpdatingDependencies(command) => say!(
+            config,
+            "Updating dependencies with: {}",
+            command.to_string().cyan()
+        ),
+        ReleaseEvent::DependenciesUpdated(stdout) => {
+            if config.verbose && !stdout.is_empty() {
+                say!(config, "Package manager output:\n{stdout}");
+            }
+            say!(config, "Successfully updated dependencies");
+        }
+        ReleaseEvent::Committed(_) => say!(config, "Committed version bump"),
+        ReleaseEvent::Tagged { name, replaced } => match (replaced, config.force_tag) {
+            (false, _) => say!(config, "Created tag: {}", name.green()),
+            (true, true) => say!(config, "Forced creation of tag: {}", name.green()),
+            (true, false) => say!(config, "Overwrote existing tag: {}", name.green()),
+        },
+        ReleaseEvent::TagSkipped(_) => say!(config, "Skipped tag creation (tag already exists)"),
+        ReleaseEvent::RolledBack => {}
+    };
+    let outcome = plan.execute(&mut observer)?;
+
+    if config.output == OutputFormat::Json {
+        let report = ReleaseReport::new(&plan, outcome);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    }
+
+    Ok(())
+}
+
+// Show what a dry run would do, with a colored unified diff per file
+fn print_plan(config: &VersionConfig, project: &dyn project::Project, plan: &ReleasePlan) {
+    let tag_name = project.get_tag_name(&plan.new_version);
+
+    if plan.changed_files().next().is_none() {
+        say!(
+            config,
+            "{} No files to update; the release is recorded by the {} tag",
             "[DRY RUN]".yellow(),
-            update_command
+            tag_name
+        );
+    }
+    for change in plan.changed_files() {
+        say!(
+            config,
+            "{} Would update {}:\n{}",
+            "[DRY RUN]".yellow(),
+            change.path.display(),
+            change.colored_diff()
         );
     }
 
-    // Git operations
-    if !config.config.no_commit && !config.config.dry_run {
-        let files_to_commit = project.get_files_to_commit();
-        if has_changelog {
-            let mut files = files_to_commit;
-            // Safe to unwrap since we checked has_changelog
-            files.push(changelog_path.unwrap());
-            git::commit_changes(&files, &format!("release: version {new_version}"))?;
-        } else {
-            git::commit_changes(&files_to_commit, &format!("release: version {new_version}"))?;
-        }
-        println!("Committed version bump");
-
-        if !config.config.no_tag {
-            let tag_name = format!("v{new_version}");
-
-            // Check if tag exists
-            if git::tag_exists(&tag_name)? {
-                if config.config.force_tag {
-                    git::create_tag(&tag_name, true)?;
-                    println!("Forced creation of tag: {}", tag_name.green());
-                } else {
-                    // Prompt user to overwrite
-                    use dialoguer::{theme::ColorfulTheme, Confirm};
-
-                    let overwrite = Confirm::with_theme(&ColorfulTheme::default())
-                        .with_prompt(format!("Tag {tag_name} already exists. Overwrite?"))
-                        .default(false)
-                        .interact()?;
-
-                    if overwrite {
-                        git::create_tag(&tag_name, true)?;
-                        println!("Overwrote existing tag: {}", tag_name.green());
-                    } else {
-                        println!("Skipped tag creation (tag already exists)");
-                    }
-                }
+    if let (Some(changelog_path), None) = (&plan.changelog, plan.changelog_change()) {
+        say!(
+            config,
+            "{} No unreleased section found in changelog {}",
+            "[DRY RUN]".yellow(),
+            changelog_path.display()
+        );
+    }
+
+    if let LockfileUpdate::Run(command) | LockfileUpdate::Skip(command) = &plan.lockfile_update {
+        say!(
+            config,
+            "{} Would update dependencies with: {}{}",
+            if matches!(plan.lockfile_update, LockfileUpdate::Skip(_)) {
+                "[NO UPDATE]".white()
+            } else {
+                "[DRY RUN]".yellow()
+            },
+            command,
+            if command.needs_network {
+                " (needs network access)"
             } else {
-                git::create_tag(&tag_name, false)?;
-                println!("Created tag: {}", tag_name.green());
+                ""
             }
-        }
-    } else if config.config.dry_run {
-        println!(
-            "{} Would commit changes and create tag v{}",
+        );
+    }
+
+    if plan.commit_message.is_some() {
+        say!(
+            config,
+            "{} Would commit changes{}",
             "[DRY RUN]".yellow(),
-            new_version
+            match &plan.tag {
+                Some(tag) if tag.exists && !tag.force =>
+                    format!(" (tag {tag_name} already exists)"),
+                Some(_) => format!(" and create tag {tag_name}"),
+                None => String::new(),
+            }
+        );
+    }
+}
+
+fn print_types(registry: &ProjectRegistry) {
+    let width = registry
+        .detectors()
+        .map(|d| d.name().len())
+        .max()
+        .unwrap_or(0);
+    for detector in registry.detectors() {
+        println!(
+            "{:<width$}  {:>4}  {}",
+            detector.name().bold(),
+            detector.priority(),
+            detector.description()
         );
     }
+}
 
+fn print_version(
+    project: &dyn project::Project,
+    version: &semver::Version,
+    format: OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Text => println!("{version}"),
+        OutputFormat::Json => {
+            let report = VersionReport {
+                project_type: project.get_project_type(),
+                manifest: project.get_file_path(),
+                version: version.to_string(),
+                locations: project.get_version_locations()?,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
     Ok(())
 }
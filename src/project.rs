@@ -1,17 +1,57 @@
-use anyhow::{anyhow, Context, Result};
+use crate::config::{self, AppVersion, HelmConfig};
+use crate::diff::{self, FileChange};
+use crate::error::{Error, Result};
+use crate::git;
+use crate::json_edit;
+use crate::lockfile;
+use crate::registry::ProjectRegistry;
+use crate::yaml_edit::{self, Segment};
+use anyhow::{anyhow, Context};
 use log::{debug, warn};
 use semver::Version;
+use serde::Serialize;
+use std::fmt;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A place in a project file where the version is recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionLocation {
+    /// The file containing the version
+    pub path: PathBuf,
+    /// The field, key or variable holding the version
+    pub field: String,
+    /// The version as written
+    pub value: String,
+}
 
 pub trait Project {
+    /// Get a short name for the kind of project, e.g. `rust` or `node`
+    fn get_project_type(&self) -> &str;
+
     fn get_version(&self) -> Result<Version>;
 
+    /// Get every place the version is recorded
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        Ok(vec![VersionLocation {
+            path: self.get_file_path().to_path_buf(),
+            field: "version".to_string(),
+            value: self.get_version()?.to_string(),
+        }])
+    }
+
     /// Update the version in the project file
-    fn update_version(&self, version: &Version) -> Result<()>;
+    fn update_version(&self, version: &Version) -> Result<()> {
+        Ok(diff::apply_all(&self.dry_run_update(version)?)?)
+    }
 
-    /// Preview what would be updated without making changes
-    fn dry_run_update(&self, version: &Version) -> Result<String>;
+    /// Compute the new content of every file updating the version would
+    /// change, without making changes
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>>;
 
     /// Get the path to the main project file
     fn get_file_path(&self) -> &Path;
@@ -19,117 +59,311 @@ pub trait Project {
     /// Get all files that should be committed
     fn get_files_to_commit(&self) -> Vec<PathBuf>;
 
+    /// Get the versions recorded for this project in its own lock file entries
+    fn get_lockfile_versions(&self) -> Result<Vec<VersionLocation>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the lock files this project owns, which are committed with the
+    /// release when updating dependencies changes them. They need not exist.
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
     /// Get the package manager update command for this project
-    fn get_package_manager_update_command(&self) -> Option<String> {
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
         None
     }
+
+    /// Compute the new content of this project's own entry in its lock files,
+    /// without running the package manager or making changes
+    fn dry_run_update_lockfiles(&self, _new: &Version) -> Result<Vec<FileChange>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the name of the git tag for a release of this project
+    fn get_tag_name(&self, version: &Version) -> String {
+        format!("v{version}")
+    }
 }
 
-pub fn detect_project(dir: &str) -> Result<Box<dyn Project>> {
-    let dir_path = Path::new(dir);
+/// A package manager invocation that refreshes a project's lock file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageManagerCommand {
+    /// The program to run
+    pub program: String,
+    /// Arguments to the program
+    pub args: Vec<String>,
+    /// Whether the command may need network access
+    pub needs_network: bool,
+    /// The lock file the command updates, which is committed with the release
+    pub lockfile: Option<PathBuf>,
+}
 
-    // Check for package.json (Node.js)
-    let package_json_path = dir_path.join("package.json");
-    if package_json_path.exists() {
-        debug!("Detected Node.js project (package.json)");
-        return Ok(Box::new(NodeProject::new(package_json_path)));
+impl PackageManagerCommand {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            needs_network: true,
+            lockfile: None,
+        }
     }
 
-    // Check for pyproject.toml (Python)
-    let pyproject_path = dir_path.join("pyproject.toml");
-    if pyproject_path.exists() {
-        debug!("Detected Python project (pyproject.toml)");
-        return Ok(Box::new(PythonProject::new(pyproject_path)));
+    /// Mark the command as working without network access
+    pub fn without_network(mut self) -> Self {
+        self.needs_network = false;
+        self
     }
 
-    // Check for Cargo.toml (Rust)
-    let cargo_path = dir_path.join("Cargo.toml");
-    if cargo_path.exists() {
-        debug!("Detected Rust project (Cargo.toml)");
-        return Ok(Box::new(RustProject::new(cargo_path)));
+    /// Set the lock file the command updates
+    pub fn with_lockfile(mut self, lockfile: PathBuf) -> Self {
+        self.lockfile = Some(lockfile);
+        self
     }
 
-    // Check for Go module (go.mod)
-    let go_mod_path = dir_path.join("go.mod");
-    if go_mod_path.exists() {
-        debug!("Detected Go project (go.mod)");
-        return Ok(Box::new(GoProject::new(go_mod_path)));
+    /// Run the command in `dir`, killing it if it hasn't finished within
+    /// `timeout`. Returns its standard output; a non-zero exit status is an error.
+    pub fn run(&self, dir: &Path, timeout: Duration) -> anyhow::Result<String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run {}", self.program))?;
+
+        // Drain the output on other threads, so that a command that writes a lot
+        // can't block on a full pipe
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(anyhow!(
+                    "`{self}` timed out after {} seconds",
+                    timeout.as_secs()
+                ));
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+        if !status.success() {
+            return Err(anyhow!("`{self}` failed ({status}): {}", stderr.trim()));
+        }
+        Ok(stdout)
     }
+}
 
-    // Check for Gemfile (Ruby)
-    let gemfile_path = dir_path.join("Gemfile");
-    if gemfile_path.exists() {
-        debug!("Detected Ruby project (Gemfile)");
-        return Ok(Box::new(RubyProject::new(gemfile_path)));
+impl fmt::Display for PackageManagerCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        Ok(())
     }
+}
 
-    let chart_path = dir_path.join("Chart.yaml");
-    if chart_path.exists() {
-        debug!("Detected Helm chart project (Chart.yaml)");
-        return Ok(Box::new(HelmChartProject::new(chart_path)));
+// Read a lock file's entries for the project with `read`, if the file exists
+fn read_lockfile_versions(
+    path: PathBuf,
+    read: impl FnOnce(&str) -> anyhow::Result<Vec<(String, String)>>,
+) -> Result<Vec<VersionLocation>> {
+    if !path.exists() {
+        return Ok(Vec::new());
     }
+    let content = read_file(&path)?;
+    let entries = read(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(entries
+        .into_iter()
+        .map(|(field, value)| VersionLocation {
+            path: path.clone(),
+            field,
+            value,
+        })
+        .collect())
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+        output
+    })
+}
+
+// Read a project file
+pub(crate) fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+// Parse a version recorded in `path`
+pub(crate) fn parse_version(path: &Path, value: &str) -> Result<Version> {
+    Version::parse(value).map_err(|source| Error::InvalidVersion {
+        path: path.to_path_buf(),
+        value: value.to_string(),
+        source,
+    })
+}
 
-    Err(anyhow!("No supported project files found in {}", dir))
+/// Detect the project in `dir` with the built-in project types and any
+/// defined in its configuration
+pub fn detect_project(dir: &str) -> Result<Box<dyn Project>> {
+    let mut registry = ProjectRegistry::default();
+    registry.register_config_types(&config::load_config(dir)?.types)?;
+    registry.detect(Path::new(dir))
 }
+
 // Helm chart project (Chart.yaml)
 pub struct HelmChartProject {
     path: PathBuf,
+    options: HelmConfig,
 }
 
 impl HelmChartProject {
     pub fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            options: HelmConfig::default(),
+        }
+    }
+
+    /// Configure how appVersion and subchart dependencies are updated
+    pub fn with_options(mut self, options: HelmConfig) -> Self {
+        self.options = options;
+        self
+    }
+
+    // The appVersion to write for a release, if it should be updated
+    fn get_new_app_version(&self, version: &Version) -> Option<String> {
+        match &self.options.app_version {
+            Some(AppVersion::Sync(true)) => Some(version.to_string()),
+            Some(AppVersion::Value(value)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    // The indices of the local (file://) subchart dependencies
+    fn get_local_dependencies(&self) -> Result<Vec<usize>> {
+        if !self.options.update_dependencies {
+            return Ok(Vec::new());
+        }
+
+        let content = read_file(&self.path)?;
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str(&content).context("Failed to parse Chart.yaml")?;
+
+        let Some(dependencies) = yaml["dependencies"].as_sequence() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(dependencies
+            .iter()
+            .enumerate()
+            .filter(|(_, dependency)| {
+                dependency["repository"]
+                    .as_str()
+                    .is_some_and(|repository| repository.starts_with("file://"))
+            })
+            .map(|(index, _)| index)
+            .collect())
     }
 
-    fn update_version_internal(&self, version: &Version, dry_run: bool) -> Result<String> {
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
         // Read the original content
-        let content = fs::read_to_string(&self.path).context("Failed to read Chart.yaml")?;
+        let content = read_file(&self.path)?;
 
-        let old_version = self.get_version()?;
+        let version_str = version.to_string();
 
-        // Using regex for targeted replacement that preserves all formatting
-        let re = regex::Regex::new(r#"(?m)^(version:\s)([^\s]+)(.*)$"#).unwrap();
-        let new_content = re.replace(&content, |caps: &regex::Captures| {
-            format!("{}{}{}", &caps[1], version, &caps[3])
-        });
+        // Edit the exact scalar nodes, preserving quoting, comments and layout
+        let mut new_content =
+            yaml_edit::replace_scalar(&content, &[Segment::Key("version")], &version_str)
+                .context("Failed to update version in Chart.yaml")?;
 
-        let diff = format!(
-            "{} Chart.yaml:\n  version: {} → {}",
-            if dry_run { "Would update" } else { "Updated" },
-            old_version,
-            version
-        );
+        if let Some(app_version) = self.get_new_app_version(version) {
+            let path = [Segment::Key("appVersion")];
+            if yaml_edit::find_scalar(&new_content, &path)?.is_some() {
+                new_content = yaml_edit::replace_scalar(&new_content, &path, &app_version)?;
+            }
+        }
 
-        if !dry_run {
-            fs::write(&self.path, new_content.as_bytes())
-                .context("Failed to write updated Chart.yaml")?;
+        // Only the umbrella chart's entries change; the subcharts keep their
+        // own versions
+        for index in self.get_local_dependencies()? {
+            let path = [
+                Segment::Key("dependencies"),
+                Segment::Index(index),
+                Segment::Key("version"),
+            ];
+            if yaml_edit::find_scalar(&new_content, &path)?.is_some() {
+                new_content = yaml_edit::replace_scalar(&new_content, &path, &version_str)?;
+            }
         }
 
-        Ok(diff)
+        Ok(vec![FileChange::new(&self.path, content, new_content)])
     }
 }
 
 impl Project for HelmChartProject {
+    fn get_project_type(&self) -> &'static str {
+        "helm"
+    }
+
     fn get_version(&self) -> Result<Version> {
-        let content = fs::read_to_string(&self.path).context("Failed to read Chart.yaml")?;
+        let content = read_file(&self.path)?;
 
         let yaml: serde_yaml::Value =
             serde_yaml::from_str(&content).context("Failed to parse Chart.yaml")?;
 
         let version_str = yaml["version"]
             .as_str()
-            .ok_or_else(|| anyhow!("No version field found in Chart.yaml"))?;
+            .ok_or_else(|| Error::MissingVersion {
+                path: self.path.clone(),
+            })?;
 
-        Version::parse(version_str).context("Failed to parse version from Chart.yaml")
+        parse_version(&self.path, version_str)
     }
 
-    fn update_version(&self, version: &Version) -> Result<()> {
-        self.update_version_internal(version, false)?;
-        Ok(())
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let content = read_file(&self.path)?;
+
+        // appVersion is independent of the chart version unless it's kept in
+        // lockstep with it
+        let fields: &[&str] = match self.options.app_version {
+            Some(AppVersion::Sync(true)) => &["version", "appVersion"],
+            _ => &["version"],
+        };
+
+        let mut locations = Vec::new();
+        for &field in fields {
+            if let Some(span) = yaml_edit::find_scalar(&content, &[Segment::Key(field)])? {
+                locations.push(VersionLocation {
+                    path: self.path.clone(),
+                    field: field.to_string(),
+                    value: span.value,
+                });
+            }
+        }
+        Ok(locations)
     }
 
-    fn dry_run_update(&self, version: &Version) -> Result<String> {
-        self.update_version_internal(version, true)
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
     }
 
     fn get_file_path(&self) -> &Path {
@@ -137,16 +371,36 @@ impl Project for HelmChartProject {
     }
 
     fn get_files_to_commit(&self) -> Vec<PathBuf> {
-        vec![self.path.clone()]
+        let mut files = vec![self.path.clone()];
+        let chart_lock_path = self.path.with_file_name("Chart.lock");
+        if chart_lock_path.exists() {
+            files.push(chart_lock_path);
+        }
+        files
+    }
+
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        vec![self.path.with_file_name("Chart.lock")]
     }
 
-    fn get_package_manager_update_command(&self) -> Option<String> {
-        // Helm doesn't have a package manager update command
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
+        // Chart.lock pins subchart versions, so it has to be regenerated when
+        // they change
+        let has_local_dependencies = self
+            .get_local_dependencies()
+            .is_ok_and(|dependencies| !dependencies.is_empty());
+        let lockfile = self.path.with_file_name("Chart.lock");
+        if has_local_dependencies && lockfile.exists() {
+            return Some(
+                PackageManagerCommand::new("helm", &["dependency", "update"])
+                    .with_lockfile(lockfile),
+            );
+        }
         None
     }
 }
 
-// Node.js project (package.json)
+// Node.js project (package.json or package.json5)
 pub struct NodeProject {
     path: PathBuf,
 }
@@ -156,64 +410,53 @@ impl NodeProject {
         Self { path }
     }
 
-    // Internal function that does the actual work, can be dry-run or real update
-    fn update_version_internal(&self, version: &Version, dry_run: bool) -> Result<String> {
-        // Read the original content
-        let content = fs::read_to_string(&self.path).context("Failed to read package.json")?;
-
-        let old_version = self.get_version()?;
-
-        // Using regex for targeted replacement that preserves all formatting
-        // We considered JSON parsing libraries but:
-        // - serde_json (even with preserve_order) loses comments and exact formatting
-        // - json-patch (RFC 6902) doesn't preserve whitespace or formatting
-        // - No suitable Rust crate exists that preserves comments and exact formatting
-        let re = regex::Regex::new(r#"("version"\s*:\s*")([^"]*)(")"#).unwrap();
-        let new_content = re.replace(&content, |caps: &regex::Captures| {
-            format!("{}{}{}", &caps[1], version, &caps[3])
-        });
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
 
-        let diff = format!(
-            "{}:\n  version: {} → {}",
-            if dry_run {
-                "Would update package.json"
-            } else {
-                "Updated package.json"
-            },
-            old_version,
-            version
-        );
+    // Compute the new content of the manifest, without writing it
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let file_name = self.file_name();
 
-        if !dry_run {
-            fs::write(&self.path, new_content.as_bytes())
-                .context("Failed to write updated package.json")?;
-        }
+        // Read the original content
+        let content = read_file(&self.path)?;
+
+        // Replace only the root object's version member, leaving nested
+        // "version" keys (engines, overrides, ...), comments and formatting alone
+        let new_content = json_edit::replace_string(
+            &content,
+            &[json_edit::Segment::Key("version")],
+            &version.to_string(),
+        )
+        .with_context(|| format!("Failed to update version in {file_name}"))?;
 
-        Ok(diff)
+        Ok(vec![FileChange::new(&self.path, content, new_content)])
     }
 }
 
 impl Project for NodeProject {
-    fn get_version(&self) -> Result<Version> {
-        let content = fs::read_to_string(&self.path).context("Failed to read package.json")?;
-
-        let package: serde_json::Value =
-            serde_json::from_str(&content).context("Failed to parse package.json")?;
+    fn get_project_type(&self) -> &'static str {
+        "node"
+    }
 
-        let version_str = package["version"]
-            .as_str()
-            .ok_or_else(|| anyhow!("No version field found in package.json"))?;
+    fn get_version(&self) -> Result<Version> {
+        let file_name = self.file_name();
+        let content = read_file(&self.path)?;
 
-        Version::parse(version_str).context("Failed to parse version from package.json")
-    }
+        let version = json_edit::find_string(&content, &[json_edit::Segment::Key("version")])
+            .with_context(|| format!("Failed to parse {file_name}"))?
+            .ok_or_else(|| Error::MissingVersion {
+                path: self.path.clone(),
+            })?;
 
-    fn update_version(&self, version: &Version) -> Result<()> {
-        self.update_version_internal(version, false)?;
-        Ok(())
+        parse_version(&self.path, &version.value)
     }
 
-    fn dry_run_update(&self, version: &Version) -> Result<String> {
-        self.update_version_internal(version, true)
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
     }
 
     fn get_file_path(&self) -> &Path {
@@ -224,22 +467,78 @@ impl Project for NodeProject {
         vec![self.path.clone()]
     }
 
-    fn get_package_manager_update_command(&self) -> Option<String> {
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        [
+            "package-lock.json",
+            "npm-shrinkwrap.json",
+            "yarn.lock",
+            "pnpm-lock.yaml",
+            "bun.lockb",
+            "bun.lock",
+        ]
+        .iter()
+        .map(|name| self.path.with_file_name(name))
+        .collect()
+    }
+
+    fn get_lockfile_versions(&self) -> Result<Vec<VersionLocation>> {
+        read_lockfile_versions(
+            self.path.with_file_name("package-lock.json"),
+            lockfile::read_package_lock,
+        )
+    }
+
+    fn dry_run_update_lockfiles(&self, new: &Version) -> Result<Vec<FileChange>> {
+        let mut changes = Vec::new();
+
+        // pnpm-lock.yaml doesn't record the project's own version (workspace
+        // packages are `link:` entries), so only `pnpm install` refreshes it
+        let package_lock_path = self.path.with_file_name("package-lock.json");
+        changes.extend(lockfile::rewrite(&package_lock_path, |content| {
+            lockfile::update_package_lock(content, new)
+        })?);
+
+        Ok(changes)
+    }
+
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
         let dir = self.path.parent().unwrap_or(Path::new("."));
 
-        // Check for different lock files to detect the package manager
-        if dir.join("bun.lockb").exists() {
-            return Some("bun install".to_string());
+        // Check for different lock files to detect the package manager, and only
+        // refresh the lock file rather than installing node_modules where the
+        // package manager supports it
+        let (command, lockfile) = if dir.join("bun.lockb").exists() {
+            (
+                PackageManagerCommand::new("bun", &["install", "--lockfile-only"]),
+                "bun.lockb",
+            )
         } else if dir.join("yarn.lock").exists() {
-            return Some("yarn".to_string());
+            // Yarn 2+ lock files start with a __metadata entry. Yarn 1 has no
+            // lockfile-only mode, so it installs the dependencies too.
+            let is_berry = fs::read_to_string(dir.join("yarn.lock"))
+                .is_ok_and(|content| content.contains("\n__metadata:"));
+            let args: &[&str] = if is_berry {
+                &["install", "--mode=update-lockfile"]
+            } else {
+                &["install"]
+            };
+            (PackageManagerCommand::new("yarn", args), "yarn.lock")
         } else if dir.join("pnpm-lock.yaml").exists() {
-            return Some("pnpm install".to_string());
+            (
+                PackageManagerCommand::new("pnpm", &["install", "--lockfile-only"]),
+                "pnpm-lock.yaml",
+            )
         } else if dir.join("package-lock.json").exists() {
-            return Some("npm install".to_string());
-        }
+            (
+                PackageManagerCommand::new("npm", &["install", "--package-lock-only"]),
+                "package-lock.json",
+            )
+        } else {
+            // Without a lock file there is nothing to refresh
+            return None;
+        };
 
-        // Default to npm if no lock file is found
-        Some("npm install".to_string())
+        Some(command.with_lockfile(dir.join(lockfile)))
     }
 }
 
@@ -253,6 +552,18 @@ impl PythonProject {
         Self { path }
     }
 
+    // Get the package name from the PEP 621 or Poetry metadata
+    fn get_package_name(&self) -> Result<Option<String>> {
+        let content = read_file(&self.path)?;
+        let toml_value: toml::Value = content.parse().context("Failed to parse pyproject.toml")?;
+        Ok(toml_value
+            .get("project")
+            .or_else(|| toml_value.get("tool")?.get("poetry"))
+            .and_then(|project| project.get("name"))
+            .and_then(|name| name.as_str())
+            .map(str::to_string))
+    }
+
     // Helper to find the version location in pyproject.toml
     fn find_version_locations(&self, content: &str) -> Result<Vec<(String, String)>> {
         let toml_value: toml::Value = content.parse().context("Failed to parse pyproject.toml")?;
@@ -284,87 +595,191 @@ impl PythonProject {
             }
         }
 
+        // Check for a dynamic version defined in a Python source file
+        if let Some((file_path, attr)) = self.find_dynamic_version_file(&toml_value) {
+            let file_content = read_file(&file_path)?;
+            let caps = python_assignment_regex(&attr)
+                .captures(&file_content)
+                .ok_or_else(|| anyhow!("No {} found in {}", attr, file_path.display()))?;
+            locations.push((
+                self.describe_version_file(&file_path, &attr),
+                caps[3].to_string(),
+            ));
+        }
+
         if locations.is_empty() {
-            return Err(anyhow!("No version field found in pyproject.toml"));
+            return Err(Error::MissingVersion {
+                path: self.path.clone(),
+            });
         }
 
         Ok(locations)
     }
-}
 
-impl PythonProject {
-    fn update_version_internal(&self, version: &Version, dry_run: bool) -> Result<String> {
-        // Read the file content
-        let content = fs::read_to_string(&self.path).context("Failed to read pyproject.toml")?;
+    // Follow `dynamic = ["version"]` to the file that defines the version, via
+    // `[tool.setuptools.dynamic] version = {attr = "pkg.__version__"}` or
+    // `[tool.hatch.version] path = "src/pkg/__about__.py"`. Returns the file and
+    // the name of the variable assigned in it.
+    fn find_dynamic_version_file(&self, toml_value: &toml::Value) -> Option<(PathBuf, String)> {
+        let is_dynamic = toml_value
+            .get("project")
+            .and_then(|p| p.get("dynamic"))
+            .and_then(|d| d.as_array())
+            .is_some_and(|d| d.iter().any(|v| v.as_str() == Some("version")));
+        if !is_dynamic {
+            return None;
+        }
 
-        // Find all version locations for reporting
-        let locations = self.find_version_locations(&content)?;
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let tool = toml_value.get("tool")?;
+
+        if let Some(attr) = tool
+            .get("setuptools")
+            .and_then(|s| s.get("dynamic"))
+            .and_then(|d| d.get("version"))
+            .and_then(|v| v.get("attr"))
+            .and_then(|a| a.as_str())
+        {
+            return resolve_python_attr(dir, attr);
+        }
+
+        if let Some(path) = tool
+            .get("hatch")
+            .and_then(|h| h.get("version"))
+            .and_then(|v| v.get("path"))
+            .and_then(|p| p.as_str())
+        {
+            let file_path = dir.join(path);
+            // Hatch's default pattern accepts either `__version__` or `VERSION`
+            let content = fs::read_to_string(&file_path).ok()?;
+            let attr = ["__version__", "VERSION"]
+                .into_iter()
+                .find(|attr| python_assignment_regex(attr).is_match(&content))?;
+            return Some((file_path, attr.to_string()));
+        }
+
+        None
+    }
+
+    // Human-readable location for a version defined in a Python file
+    fn describe_version_file(&self, file_path: &Path, attr: &str) -> String {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let relative = file_path.strip_prefix(dir).unwrap_or(file_path);
+        format!("{}:{attr}", relative.display())
+    }
+}
 
-        let prefix = if dry_run { "Would update" } else { "Updated" };
-        let mut diff = format!("{prefix} pyproject.toml:");
+// Resolve a setuptools-style `pkg.module.__version__` attribute reference to the
+// module's source file, looking in the project root and a `src/` layout
+fn resolve_python_attr(dir: &Path, attr_ref: &str) -> Option<(PathBuf, String)> {
+    let (module, attr) = attr_ref.rsplit_once('.')?;
+    let module_path: PathBuf = module.split('.').collect();
 
-        for (location, old_version) in &locations {
-            diff.push_str(&format!("\n  {location}: {old_version} → {version}"));
+    for root in [dir.to_path_buf(), dir.join("src")] {
+        let candidates = [
+            root.join(&module_path).with_extension("py"),
+            root.join(&module_path).join("__init__.py"),
+        ];
+        if let Some(path) = candidates.into_iter().find(|path| path.exists()) {
+            return Some((path, attr.to_string()));
         }
+    }
 
-        if !dry_run {
-            // Parse the TOML with toml_edit to preserve formatting, spacing, and comments
-            let mut doc = match content.parse::<toml_edit::DocumentMut>() {
-                Ok(doc) => doc,
-                Err(e) => return Err(anyhow!("Failed to parse pyproject.toml: {}", e)),
-            };
+    warn!("Could not find the Python module for version attribute {attr_ref}");
+    None
+}
 
-            // Update each known version location
-            let version_str = version.to_string();
+// Matches a `name = "x.y.z"` assignment at the start of a line, optionally with a
+// type annotation. Groups: 1 = everything before the quote, 2 = opening quote,
+// 3 = value, 4 = closing quote.
+fn python_assignment_regex(name: &str) -> regex::Regex {
+    regex::Regex::new(&format!(
+        r#"(?m)^({}\s*(?::\s*[\w\[\]]+\s*)?=\s*)(['"])([^'"]*)(['"])"#,
+        regex::escape(name)
+    ))
+    .unwrap()
+}
 
-            // Check project.version (PEP 621)
-            if let Some(project) = doc.get_mut("project") {
-                if let Some(project_table) = project.as_table_mut() {
-                    if project_table.contains_key("version") {
-                        project_table["version"] = toml_edit::value(version_str.clone());
-                    }
+impl PythonProject {
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        // Read the file content
+        let content = read_file(&self.path)?;
+
+        // Parse the TOML with toml_edit to preserve formatting, spacing, and comments
+        let mut doc = match content.parse::<toml_edit::DocumentMut>() {
+            Ok(doc) => doc,
+            Err(e) => return Err(anyhow!("Failed to parse pyproject.toml: {}", e).into()),
+        };
+
+        // Update each known version location
+        let version_str = version.to_string();
+
+        // Check project.version (PEP 621)
+        if let Some(project) = doc.get_mut("project") {
+            if let Some(project_table) = project.as_table_mut() {
+                if project_table.contains_key("version") {
+                    project_table["version"] = toml_edit::value(version_str.clone());
                 }
             }
+        }
 
-            // Check tool.poetry.version
-            if let Some(tool) = doc.get_mut("tool") {
-                if let Some(tool_table) = tool.as_table_mut() {
-                    if let Some(poetry) = tool_table.get_mut("poetry") {
-                        if let Some(poetry_table) = poetry.as_table_mut() {
-                            if poetry_table.contains_key("version") {
-                                poetry_table["version"] = toml_edit::value(version_str.clone());
-                            }
+        // Check tool.poetry.version
+        if let Some(tool) = doc.get_mut("tool") {
+            if let Some(tool_table) = tool.as_table_mut() {
+                if let Some(poetry) = tool_table.get_mut("poetry") {
+                    if let Some(poetry_table) = poetry.as_table_mut() {
+                        if poetry_table.contains_key("version") {
+                            poetry_table["version"] = toml_edit::value(version_str.clone());
                         }
                     }
+                }
 
-                    // Check tool.setuptools.version
-                    if let Some(setuptools) = tool_table.get_mut("setuptools") {
-                        if let Some(setuptools_table) = setuptools.as_table_mut() {
-                            if setuptools_table.contains_key("version") {
-                                setuptools_table["version"] = toml_edit::value(version_str.clone());
-                            }
+                // Check tool.setuptools.version
+                if let Some(setuptools) = tool_table.get_mut("setuptools") {
+                    if let Some(setuptools_table) = setuptools.as_table_mut() {
+                        if setuptools_table.contains_key("version") {
+                            setuptools_table["version"] = toml_edit::value(version_str.clone());
                         }
                     }
                 }
             }
+        }
 
-            // Write the updated TOML
-            let new_content = doc.to_string();
-            if new_content == content {
-                warn!("No version patterns matched in pyproject.toml");
-                return Err(anyhow!("Failed to update version in pyproject.toml"));
-            }
+        let mut changes = Vec::new();
+
+        // Update the `__version__` assignment behind a dynamic version
+        let toml_value: toml::Value = content.parse().context("Failed to parse pyproject.toml")?;
+        if let Some((file_path, attr)) = self.find_dynamic_version_file(&toml_value) {
+            let file_content = read_file(&file_path)?;
+            let new_file_content = python_assignment_regex(&attr)
+                .replace(&file_content, |caps: &regex::Captures| {
+                    format!("{}{}{}{}", &caps[1], &caps[2], version, &caps[4])
+                })
+                .into_owned();
+            changes.push(FileChange::new(file_path, file_content, new_file_content));
+        }
+
+        let new_content = doc.to_string();
+        if new_content == content && changes.is_empty() {
+            warn!("No version patterns matched in pyproject.toml");
+            return Err(anyhow!("Failed to update version in pyproject.toml").into());
+        }
 
-            fs::write(&self.path, new_content).context("Failed to write updated pyproject.toml")?;
+        if new_content != content {
+            changes.insert(0, FileChange::new(&self.path, content, new_content));
         }
 
-        Ok(diff)
+        Ok(changes)
     }
 }
 
 impl Project for PythonProject {
+    fn get_project_type(&self) -> &'static str {
+        "python"
+    }
+
     fn get_version(&self) -> Result<Version> {
-        let content = fs::read_to_string(&self.path).context("Failed to read pyproject.toml")?;
+        let content = read_file(&self.path)?;
 
         // Find all version locations
         let locations = self.find_version_locations(&content)?;
@@ -372,16 +787,41 @@ impl Project for PythonProject {
         // Use the first one
         let version_str = &locations[0].1;
 
-        Version::parse(version_str).context("Failed to parse version from pyproject.toml")
+        parse_version(&self.path, version_str)
     }
 
-    fn update_version(&self, version: &Version) -> Result<()> {
-        self.update_version_internal(version, false)?;
-        Ok(())
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let content = read_file(&self.path)?;
+        let toml_value: toml::Value = content.parse().context("Failed to parse pyproject.toml")?;
+
+        // A dynamic version is reported in the file that defines it
+        let version_file = self.find_dynamic_version_file(&toml_value);
+        let version_file_description = version_file
+            .as_ref()
+            .map(|(file_path, attr)| self.describe_version_file(file_path, attr));
+
+        let locations = self.find_version_locations(&content)?;
+        Ok(locations
+            .into_iter()
+            .map(|(field, value)| match &version_file {
+                Some((file_path, attr)) if version_file_description.as_ref() == Some(&field) => {
+                    VersionLocation {
+                        path: file_path.clone(),
+                        field: attr.clone(),
+                        value,
+                    }
+                }
+                _ => VersionLocation {
+                    path: self.path.clone(),
+                    field,
+                    value,
+                },
+            })
+            .collect())
     }
 
-    fn dry_run_update(&self, version: &Version) -> Result<String> {
-        self.update_version_internal(version, true)
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
     }
 
     fn get_file_path(&self) -> &Path {
@@ -389,128 +829,233 @@ impl Project for PythonProject {
     }
 
     fn get_files_to_commit(&self) -> Vec<PathBuf> {
-        vec![self.path.clone()]
+        let mut files = vec![self.path.clone()];
+
+        let version_file = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| content.parse::<toml::Value>().ok())
+            .and_then(|toml_value| self.find_dynamic_version_file(&toml_value));
+        if let Some((file_path, _)) = version_file {
+            files.push(file_path);
+        }
+
+        files
     }
 
-    fn get_package_manager_update_command(&self) -> Option<String> {
-        let dir = self.path.parent().unwrap_or(Path::new("."));
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        ["uv.lock", "poetry.lock", "Pipfile.lock", "pdm.lock"]
+            .iter()
+            .map(|name| self.path.with_file_name(name))
+            .collect()
+    }
 
-        // Check for different lock files and configurations to detect the package manager
-        if dir.join("poetry.lock").exists() {
-            return Some("poetry update".to_string());
-        } else if dir.join("Pipfile.lock").exists() {
-            return Some("pipenv update".to_string());
-        } else if dir.join("pdm.lock").exists() {
-            return Some("pdm update".to_string());
-        }
+    fn get_lockfile_versions(&self) -> Result<Vec<VersionLocation>> {
+        let Some(name) = self.get_package_name()? else {
+            return Ok(Vec::new());
+        };
 
-        // Check for the presence of uv files or directories
-        if dir.join("uv.lock").exists() || dir.join(".uv").exists() {
-            return Some("uv sync".to_string());
-        }
+        let mut versions =
+            read_lockfile_versions(self.path.with_file_name("uv.lock"), |content| {
+                lockfile::read_uv_lock(content, &name)
+            })?;
+        versions.extend(read_lockfile_versions(
+            self.path.with_file_name("poetry.lock"),
+            |content| lockfile::read_poetry_lock(content, &name),
+        )?);
+        Ok(versions)
+    }
 
-        // Read the content of the pyproject.toml to detect Python package tool
-        if let Ok(content) = fs::read_to_string(&self.path) {
-            if content.contains("[tool.poetry]") {
-                return Some("poetry update".to_string());
-            } else if content.contains("[tool.pdm]") {
-                return Some("pdm update".to_string());
-            } else if content.contains("[tool.hatch") {
-                return Some("hatch env update".to_string());
-            }
-        }
+    fn dry_run_update_lockfiles(&self, new: &Version) -> Result<Vec<FileChange>> {
+        let Some(name) = self.get_package_name()? else {
+            return Ok(Vec::new());
+        };
+        let name = name.as_str();
 
-        // Default to pip
-        if dir.join("requirements.txt").exists() {
-            return Some("pip install -r requirements.txt".to_string());
-        }
+        let mut changes = Vec::new();
 
-        // Return None if we can't confidently determine the package manager
-        None
+        let uv_lock_path = self.path.with_file_name("uv.lock");
+        changes.extend(lockfile::rewrite(&uv_lock_path, |content| {
+            lockfile::update_uv_lock(content, name, new)
+        })?);
+
+        let poetry_lock_path = self.path.with_file_name("poetry.lock");
+        changes.extend(lockfile::rewrite(&poetry_lock_path, |content| {
+            lockfile::update_poetry_lock(content, name, new)
+        })?);
+
+        Ok(changes)
+    }
+
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+
+        // Check for different lock files to detect the package manager. Each
+        // command re-locks while keeping the currently locked dependency versions.
+        let (command, lockfile) = if dir.join("uv.lock").exists() {
+            (PackageManagerCommand::new("uv", &["lock"]), "uv.lock")
+        } else if dir.join("poetry.lock").exists() {
+            // Since Poetry 2, `poetry lock` doesn't upgrade locked dependencies;
+            // before that it needs --no-update. The lock file records the
+            // Poetry version that generated it.
+            let poetry_1 = fs::read_to_string(dir.join("poetry.lock"))
+                .is_ok_and(|content| content.contains("@generated by Poetry 1."));
+            let args: &[&str] = if poetry_1 {
+                &["lock", "--no-update"]
+            } else {
+                &["lock"]
+            };
+            (PackageManagerCommand::new("poetry", args), "poetry.lock")
+        } else if dir.join("Pipfile.lock").exists() {
+            (
+                PackageManagerCommand::new("pipenv", &["lock"]),
+                "Pipfile.lock",
+            )
+        } else if dir.join("pdm.lock").exists() {
+            (
+                PackageManagerCommand::new("pdm", &["lock", "--update-reuse"]),
+                "pdm.lock",
+            )
+        } else {
+            // Return None if there is no lock file to refresh
+            return None;
+        };
+
+        Some(command.with_lockfile(dir.join(lockfile)))
     }
 }
 
-// Rust project (Cargo.toml)
-pub struct RustProject {
+// Legacy Python project (setup.cfg or setup.py)
+pub struct SetuptoolsProject {
     path: PathBuf,
 }
 
-impl RustProject {
+impl SetuptoolsProject {
     pub fn new(path: PathBuf) -> Self {
         Self { path }
     }
-}
 
-impl RustProject {
-    fn update_version_internal(&self, version: &Version, dry_run: bool) -> Result<String> {
-        let content = fs::read_to_string(&self.path).context("Failed to read Cargo.toml")?;
+    /// Find the setup file that declares the version, preferring a setup.cfg
+    /// `[metadata]` section over setup.py
+    pub fn find_setup_file(dir: &Path) -> Option<PathBuf> {
+        let setup_cfg_path = dir.join("setup.cfg");
+        let setup_py_path = dir.join("setup.py");
+
+        let cfg_has_version = fs::read_to_string(&setup_cfg_path)
+            .is_ok_and(|content| find_setup_cfg_version(&content).is_some());
+
+        if cfg_has_version || (setup_cfg_path.exists() && !setup_py_path.exists()) {
+            Some(setup_cfg_path)
+        } else if setup_py_path.exists() {
+            Some(setup_py_path)
+        } else {
+            None
+        }
+    }
 
-        let old_version = self.get_version()?;
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
 
-        let prefix = if dry_run { "Would update" } else { "Updated" };
-        let diff = format!("{prefix} Cargo.toml:\n  version: {old_version} → {version}");
+    // Locate the version string literal, following `version = attr: pkg.__version__`
+    // in setup.cfg. Returns the file containing the literal, the byte range of the
+    // literal, and a description of the location.
+    fn find_version_span(&self) -> Result<(PathBuf, std::ops::Range<usize>, String)> {
+        let file_name = self.file_name();
+        let content = read_file(&self.path)?;
+
+        if file_name == "setup.py" {
+            // Only look at the keyword arguments of the setup() call
+            let setup_start = content
+                .find("setup(")
+                .ok_or_else(|| anyhow!("No setup() call found in setup.py"))?;
+            let version_re = regex::Regex::new(r#"\bversion\s*=\s*['"]([^'"]*)['"]"#).unwrap();
+            let literal = version_re
+                .captures(&content[setup_start..])
+                .and_then(|caps| caps.get(1))
+                .ok_or_else(|| anyhow!("No version string literal found in setup.py"))?;
+            let start = setup_start + literal.start();
+            return Ok((
+                self.path.clone(),
+                start..start + literal.len(),
+                "setup(version=...)".to_string(),
+            ));
+        }
 
-        if !dry_run {
-            // Parse the TOML with toml_edit to preserve formatting, spacing, and comments
-            let mut doc = match content.parse::<toml_edit::DocumentMut>() {
-                Ok(doc) => doc,
-                Err(e) => return Err(anyhow!("Failed to parse Cargo.toml: {}", e)),
-            };
+        let span = find_setup_cfg_version(&content).ok_or_else(|| Error::MissingVersion {
+            path: self.path.clone(),
+        })?;
 
-            let package = if doc.contains_key("package") {
-                doc.get_mut("package")
-            } else {
-                doc.get_mut("workspace")
-                    .and_then(|workspace| workspace.get_mut("package"))
-            };
+        let value = &content[span.clone()];
+        let Some(attr_ref) = value.strip_prefix("attr:") else {
+            return Ok((self.path.clone(), span, "metadata.version".to_string()));
+        };
 
-            // Update the package.version
-            if let Some(package) = package {
-                if let Some(package_table) = package.as_table_mut() {
-                    if package_table.contains_key("version") {
-                        package_table["version"] = toml_edit::value(version.to_string());
-                    } else {
-                        return Err(anyhow!(
-                            "No version field found in Cargo.toml package table"
-                        ));
-                    }
-                }
-            } else {
-                return Err(anyhow!("No package table found in Cargo.toml"));
-            }
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let attr_ref = attr_ref.trim();
+        let (file_path, attr) = resolve_python_attr(dir, attr_ref)
+            .ok_or_else(|| anyhow!("Could not resolve version attribute {}", attr_ref))?;
+        let file_content = read_file(&file_path)?;
+        let literal = python_assignment_regex(&attr)
+            .captures(&file_content)
+            .and_then(|caps| caps.get(3))
+            .ok_or_else(|| anyhow!("No {} found in {}", attr, file_path.display()))?;
+
+        let relative = file_path.strip_prefix(dir).unwrap_or(&file_path);
+        let location = format!("{}:{attr}", relative.display());
+        Ok((file_path.clone(), literal.range(), location))
+    }
 
-            // Write the updated TOML
-            let new_content = doc.to_string();
-            fs::write(&self.path, new_content).context("Failed to write updated Cargo.toml")?;
-        }
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let (file_path, span, _) = self.find_version_span()?;
+        let content = read_file(&file_path)?;
 
-        Ok(diff)
+        // Splice in the new version so the rest of the file is untouched
+        let mut new_content = content.clone();
+        new_content.replace_range(span, &version.to_string());
+
+        Ok(vec![FileChange::new(file_path, content, new_content)])
     }
 }
 
-impl Project for RustProject {
-    fn get_version(&self) -> Result<Version> {
-        let content = fs::read_to_string(&self.path).context("Failed to read Cargo.toml")?;
-
-        let toml_value: toml::Value = content.parse().context("Failed to parse Cargo.toml")?;
+// Find the byte range of the `version` value in the `[metadata]` section of a
+// setup.cfg, excluding surrounding whitespace
+fn find_setup_cfg_version(content: &str) -> Option<std::ops::Range<usize>> {
+    let version_re = regex::Regex::new(r"^version\s*[=:]\s*(.*?)\s*$").unwrap();
+
+    let mut in_metadata = false;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_metadata = trimmed == "[metadata]";
+        } else if in_metadata {
+            if let Some(value) = version_re.captures(line).and_then(|caps| caps.get(1)) {
+                return Some(offset + value.start()..offset + value.end());
+            }
+        }
+        offset += line.len();
+    }
 
-        let version_str = toml_value
-            .get("package")
-            .or_else(|| toml_value.get("workspace")?.get("package"))
-            .and_then(|package| package.get("version"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("No version field found in Cargo.toml"))?;
+    None
+}
 
-        Version::parse(version_str).context("Failed to parse version from Cargo.toml")
+impl Project for SetuptoolsProject {
+    fn get_project_type(&self) -> &'static str {
+        "setuptools"
     }
 
-    fn update_version(&self, version: &Version) -> Result<()> {
-        self.update_version_internal(version, false)?;
-        Ok(())
+    fn get_version(&self) -> Result<Version> {
+        let (file_path, span, _) = self.find_version_span()?;
+        let content = read_file(&file_path)?;
+
+        parse_version(&file_path, &content[span])
     }
 
-    fn dry_run_update(&self, version: &Version) -> Result<String> {
-        self.update_version_internal(version, true)
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
     }
 
     fn get_file_path(&self) -> &Path {
@@ -518,34 +1063,223 @@ impl Project for RustProject {
     }
 
     fn get_files_to_commit(&self) -> Vec<PathBuf> {
-        vec![self.path.clone()]
-    }
-
-    fn get_package_manager_update_command(&self) -> Option<String> {
-        // Cargo is the only package manager for Rust
-        Some("cargo update".to_string())
+        let mut files = vec![self.path.clone()];
+        if let Ok((file_path, _, _)) = self.find_version_span() {
+            if file_path != self.path {
+                files.push(file_path);
+            }
+        }
+        files
     }
 }
 
-// Go project (go.mod)
-pub struct GoProject {
+// Rust project (Cargo.toml)
+pub struct RustProject {
     path: PathBuf,
 }
 
-impl GoProject {
+impl RustProject {
     pub fn new(path: PathBuf) -> Self {
         Self { path }
     }
-}
 
-impl GoProject {
-    fn get_version_files(&self) -> Vec<PathBuf> {
-        let dir = self.path.parent().unwrap_or(Path::new("."));
-        let candidates = [
-            dir.join("version.go"),
-            dir.join("internal/version/version.go"),
-            dir.join("pkg/version/version.go"),
-        ];
+    // Names of the packages whose version this project sets: the root package,
+    // and the workspace members that inherit `workspace.package.version`
+    fn get_package_names(&self) -> Result<Vec<String>> {
+        let content = read_file(&self.path)?;
+        let toml_value: toml::Value = content.parse().context("Failed to parse Cargo.toml")?;
+
+        let inherits_version = |manifest: &toml::Value| {
+            manifest
+                .get("package")
+                .and_then(|package| package.get("version"))
+                .and_then(|version| version.get("workspace"))
+                .and_then(|workspace| workspace.as_bool())
+                == Some(true)
+        };
+        let package_name = |manifest: &toml::Value| {
+            manifest
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(|name| name.as_str())
+                .map(str::to_string)
+        };
+
+        let mut names: Vec<String> = package_name(&toml_value).into_iter().collect();
+
+        let Some(workspace) = toml_value.get("workspace") else {
+            return Ok(names);
+        };
+        if workspace
+            .get("package")
+            .and_then(|p| p.get("version"))
+            .is_none()
+        {
+            return Ok(names);
+        }
+
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let members = workspace
+            .get("members")
+            .and_then(|members| members.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|member| member.as_str());
+        for member in members {
+            let pattern = dir.join(member).join("Cargo.toml");
+            let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
+                continue;
+            };
+            for path in paths.filter_map(Result::ok) {
+                let manifest = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| content.parse::<toml::Value>().ok());
+                if let Some(manifest) = manifest.filter(inherits_version) {
+                    names.extend(package_name(&manifest));
+                }
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+impl RustProject {
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let content = read_file(&self.path)?;
+
+        // Parse the TOML with toml_edit to preserve formatting, spacing, and comments
+        let mut doc = match content.parse::<toml_edit::DocumentMut>() {
+            Ok(doc) => doc,
+            Err(e) => return Err(anyhow!("Failed to parse Cargo.toml: {}", e).into()),
+        };
+
+        let package = if doc.contains_key("package") {
+            doc.get_mut("package")
+        } else {
+            doc.get_mut("workspace")
+                .and_then(|workspace| workspace.get_mut("package"))
+        };
+
+        // Update the package.version
+        if let Some(package) = package {
+            if let Some(package_table) = package.as_table_mut() {
+                if package_table.contains_key("version") {
+                    package_table["version"] = toml_edit::value(version.to_string());
+                } else {
+                    return Err(Error::MissingVersion {
+                        path: self.path.clone(),
+                    });
+                }
+            }
+        } else {
+            return Err(anyhow!("No package table found in Cargo.toml").into());
+        }
+
+        let new_content = doc.to_string();
+        Ok(vec![FileChange::new(&self.path, content, new_content)])
+    }
+}
+
+impl Project for RustProject {
+    fn get_project_type(&self) -> &'static str {
+        "rust"
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        let content = read_file(&self.path)?;
+
+        let toml_value: toml::Value = content.parse().context("Failed to parse Cargo.toml")?;
+
+        let version_str = toml_value
+            .get("package")
+            .or_else(|| toml_value.get("workspace")?.get("package"))
+            .and_then(|package| package.get("version"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::MissingVersion {
+                path: self.path.clone(),
+            })?;
+
+        parse_version(&self.path, version_str)
+    }
+
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let content = read_file(&self.path)?;
+        let toml_value: toml::Value = content.parse().context("Failed to parse Cargo.toml")?;
+
+        let field = if toml_value.get("package").is_some() {
+            "package.version"
+        } else {
+            "workspace.package.version"
+        };
+        Ok(vec![VersionLocation {
+            path: self.path.clone(),
+            field: field.to_string(),
+            value: self.get_version()?.to_string(),
+        }])
+    }
+
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
+    }
+
+    fn get_file_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn get_files_to_commit(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        vec![self.path.with_file_name("Cargo.lock")]
+    }
+
+    fn get_lockfile_versions(&self) -> Result<Vec<VersionLocation>> {
+        let names = self.get_package_names()?;
+        read_lockfile_versions(self.path.with_file_name("Cargo.lock"), |content| {
+            lockfile::read_cargo_lock(content, &names)
+        })
+    }
+
+    fn dry_run_update_lockfiles(&self, new: &Version) -> Result<Vec<FileChange>> {
+        let names = self.get_package_names()?;
+        let cargo_lock_path = self.path.with_file_name("Cargo.lock");
+        let change = lockfile::rewrite(&cargo_lock_path, |content| {
+            lockfile::update_cargo_lock(content, &names, new)
+        })?;
+        Ok(change.into_iter().collect())
+    }
+
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
+        // Only the workspace's own packages need updating, which Cargo can do
+        // from its local index cache
+        let command = PackageManagerCommand::new("cargo", &["update", "--workspace", "--offline"])
+            .without_network()
+            .with_lockfile(self.path.with_file_name("Cargo.lock"));
+        Some(command)
+    }
+}
+
+// Go project (go.mod)
+pub struct GoProject {
+    path: PathBuf,
+}
+
+impl GoProject {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl GoProject {
+    fn get_version_files(&self) -> Vec<PathBuf> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let candidates = [
+            dir.join("version.go"),
+            dir.join("internal/version/version.go"),
+            dir.join("pkg/version/version.go"),
+        ];
 
         candidates
             .into_iter()
@@ -553,26 +1287,123 @@ impl GoProject {
             .collect()
     }
 
-    fn update_version_internal(&self, version: &Version, dry_run: bool) -> Result<String> {
-        let version_files = self.get_version_files();
-        let mut updated_files = Vec::new();
-        let mut diff = String::new();
+    // Read the module path from the `module` directive in go.mod
+    fn get_module_path(&self) -> Result<String> {
+        let content = read_file(&self.path)?;
+
+        let module_re = regex::Regex::new(r"(?m)^module\s+(\S+)").unwrap();
+        let caps = module_re
+            .captures(&content)
+            .ok_or_else(|| anyhow!("No module directive found in go.mod"))?;
+
+        Ok(caps[1].trim_matches('"').to_string())
+    }
+
+    // Tag prefix for modules nested below the repository root, e.g. `tools/` for a
+    // module in tools/go.mod, so tags follow Go's `tools/vX.Y.Z` convention
+    fn get_tag_prefix(&self) -> String {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+
+        let (Ok(dir), Some(root)) = (dir.canonicalize(), git::repo_root(dir)) else {
+            return String::new();
+        };
+
+        match dir.strip_prefix(&root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => {
+                let components: Vec<_> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                format!("{}/", components.join("/"))
+            }
+            _ => String::new(),
+        }
+    }
+
+    // Under semantic import versioning, a module at major version 2 or above has a
+    // `/vN` suffix on its path. Returns the old and new module paths if changing
+    // from `old_version` to `new_version` requires a new path.
+    fn get_module_path_migration(
+        &self,
+        old_version: &Version,
+        new_version: &Version,
+    ) -> Result<Option<(String, String)>> {
+        if old_version.major == new_version.major {
+            return Ok(None);
+        }
 
-        let old_version = match self.get_version() {
-            Ok(v) => v,
-            Err(_) => Version::new(0, 1, 0),
+        let old_path = self.get_module_path()?;
+        let suffix_re = regex::Regex::new(r"/v[0-9]+$").unwrap();
+        let base_path = suffix_re.replace(&old_path, "");
+        let new_path = if new_version.major >= 2 {
+            format!("{base_path}/v{}", new_version.major)
+        } else {
+            base_path.to_string()
         };
 
-        diff.push_str(&format!(
-            "{} Go project version from {} to {}:\n",
-            if dry_run { "Would update" } else { "Updated" },
-            old_version,
-            version
-        ));
+        if new_path == old_path {
+            return Ok(None);
+        }
+        Ok(Some((old_path, new_path)))
+    }
+
+    // Matches a string literal naming the module or one of its packages
+    fn import_regex(module_path: &str) -> regex::Regex {
+        regex::Regex::new(&format!(r#""{}(/[^"]*)?""#, regex::escape(module_path))).unwrap()
+    }
+
+    // Go source files in this module that import the module's own packages
+    fn get_files_importing(&self, module_path: &str) -> Vec<PathBuf> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let mut go_files = Vec::new();
+        collect_go_files(dir, &mut go_files);
+
+        let import_re = Self::import_regex(module_path);
+        go_files
+            .into_iter()
+            .filter(|path| {
+                fs::read_to_string(path).is_ok_and(|content| import_re.is_match(&content))
+            })
+            .collect()
+    }
+
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let version_files = self.get_version_files();
+        let mut changes = Vec::new();
 
-        if version_files.is_empty() {
-            diff.push_str("  No version files found. You may need to manually create/update version information.");
-            return Ok(diff);
+        let old_version = self.get_version()?;
+
+        // Changing to a v2+ major version moves the module to a new path, which
+        // every import of the module's own packages has to follow
+        if let Some((old_path, new_path)) = self.get_module_path_migration(&old_version, version)? {
+            let module_re = regex::Regex::new(&format!(
+                r#"(?m)^(module\s+"?){}("?\s*)$"#,
+                regex::escape(&old_path)
+            ))
+            .unwrap();
+            stage_change(&mut changes, &self.path, |content| {
+                module_re
+                    .replace(content, |caps: &regex::Captures| {
+                        format!("{}{}{}", &caps[1], new_path, &caps[2])
+                    })
+                    .into_owned()
+            })?;
+
+            let import_re = Self::import_regex(&old_path);
+            for file_path in &self.get_files_importing(&old_path) {
+                stage_change(&mut changes, file_path, |content| {
+                    import_re
+                        .replace_all(content, |caps: &regex::Captures| {
+                            format!("\"{}{}\"", new_path, caps.get(1).map_or("", |m| m.as_str()))
+                        })
+                        .into_owned()
+                })?;
+            }
         }
 
         let version_regex = regex::Regex::new(
@@ -580,35 +1411,49 @@ impl GoProject {
         )
         .unwrap();
 
+        let mut matched = false;
         for file_path in &version_files {
-            let file_content =
-                fs::read_to_string(file_path).context("Failed to read version file")?;
-
-            if version_regex.is_match(&file_content) {
-                diff.push_str(&format!("  File: {}\n", file_path.display()));
-
-                let new_content = version_regex.replace(&file_content, |caps: &regex::Captures| {
-                    format!("{}v{}{}", &caps[1], version, &caps[3])
-                });
-
-                if !dry_run && new_content != file_content {
-                    fs::write(file_path, new_content.as_bytes())
-                        .context("Failed to write updated version file")?;
-                    updated_files.push(file_path);
-                }
-            }
+            stage_change(&mut changes, file_path, |content| {
+                matched |= version_regex.is_match(content);
+                version_regex
+                    .replace(content, |caps: &regex::Captures| {
+                        format!("{}v{}{}", &caps[1], version, &caps[3])
+                    })
+                    .into_owned()
+            })?;
         }
 
-        if !dry_run && updated_files.is_empty() {
+        if !version_files.is_empty() && !matched {
             warn!("No version file was updated for Go project.");
-            diff.push_str("  No version patterns were matched in any files.");
         }
 
-        Ok(diff)
+        Ok(changes.into_iter().filter(FileChange::is_changed).collect())
     }
 }
 
+// Apply `update` to the pending content of `path`, starting from the file on
+// disk if no earlier change touched it
+fn stage_change(
+    changes: &mut Vec<FileChange>,
+    path: &Path,
+    update: impl FnOnce(&str) -> String,
+) -> Result<()> {
+    if let Some(change) = changes.iter_mut().find(|change| change.path == path) {
+        change.updated = update(&change.updated);
+        return Ok(());
+    }
+
+    let content = read_file(path)?;
+    let new_content = update(&content);
+    changes.push(FileChange::new(path, content, new_content));
+    Ok(())
+}
+
 impl Project for GoProject {
+    fn get_project_type(&self) -> &'static str {
+        "go"
+    }
+
     fn get_version(&self) -> Result<Version> {
         let version_files = self.get_version_files();
 
@@ -617,46 +1462,118 @@ impl Project for GoProject {
                 .unwrap();
 
         for file_path in &version_files {
-            let version_content =
-                fs::read_to_string(file_path).context("Failed to read version file")?;
+            let version_content = read_file(file_path)?;
 
             if let Some(captures) = version_regex.captures(&version_content) {
                 if let Some(version_match) = captures.get(1) {
-                    return Version::parse(version_match.as_str())
-                        .context("Failed to parse version from version.go file");
+                    return parse_version(file_path, version_match.as_str());
                 }
             }
         }
 
-        // Fallback: use git tags
-        warn!("Could not find version information in Go files, you may need to manually tag this version");
+        // Fallback: most Go modules are versioned by their git tags alone
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let prefix = self.get_tag_prefix();
+        if let Some((tag, version)) = git::latest_version_tag(dir, &prefix) {
+            debug!("Using version from git tag {tag}");
+            return Ok(version);
+        }
 
-        // Default to 0.1.0 if we can't determine it
-        Ok(Version::new(0, 1, 0))
+        warn!("No version.go file or {prefix}vX.Y.Z tag found; assuming no release yet");
+        Ok(Version::new(0, 0, 0))
     }
 
-    fn update_version(&self, version: &Version) -> Result<()> {
-        self.update_version_internal(version, false)?;
-        Ok(())
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let version_regex =
+            regex::Regex::new(r#"(Version|VERSION)\s*=\s*["']v?([0-9]+\.[0-9]+\.[0-9]+)["']"#)
+                .unwrap();
+
+        let mut locations = Vec::new();
+        for file_path in self.get_version_files() {
+            let content = read_file(&file_path)?;
+            if let Some(caps) = version_regex.captures(&content) {
+                locations.push(VersionLocation {
+                    path: file_path,
+                    field: caps[1].to_string(),
+                    value: caps[2].to_string(),
+                });
+            }
+        }
+
+        // Modules without a version constant are versioned by their tags
+        if locations.is_empty() {
+            let dir = self.path.parent().unwrap_or(Path::new("."));
+            if let Some((tag, version)) = git::latest_version_tag(dir, &self.get_tag_prefix()) {
+                locations.push(VersionLocation {
+                    path: dir.to_path_buf(),
+                    field: format!("tag {tag}"),
+                    value: version.to_string(),
+                });
+            }
+        }
+
+        Ok(locations)
     }
 
-    fn dry_run_update(&self, version: &Version) -> Result<String> {
-        self.update_version_internal(version, true)
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
     }
 
     fn get_file_path(&self) -> &Path {
         &self.path
     }
 
+    // Files rewritten by a module path migration are committed through the
+    // release's changes, so unrelated edits to importing files stay unstaged
     fn get_files_to_commit(&self) -> Vec<PathBuf> {
         let mut files = vec![self.path.clone()];
         files.extend(self.get_version_files());
         files
     }
 
-    fn get_package_manager_update_command(&self) -> Option<String> {
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        vec![self.path.with_file_name("go.sum")]
+    }
+
+    fn get_tag_name(&self, version: &Version) -> String {
+        format!("{}v{version}", self.get_tag_prefix())
+    }
+
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
         // Go modules has a specific update command
-        Some("go mod tidy".to_string())
+        let command = PackageManagerCommand::new("go", &["mod", "tidy"])
+            .with_lockfile(self.path.with_file_name("go.sum"));
+        Some(command)
+    }
+}
+
+// Recursively collect the *.go files of a module, skipping vendored code, test
+// fixtures, hidden directories and nested modules
+fn collect_go_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut paths: Vec<PathBuf> = entries.filter_map(Result::ok).map(|e| e.path()).collect();
+    paths.sort();
+
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if name.starts_with('.')
+                || name == "vendor"
+                || name == "testdata"
+                || path.join("go.mod").exists()
+            {
+                continue;
+            }
+            collect_go_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "go") {
+            files.push(path);
+        }
     }
 }
 
@@ -665,6 +1582,14 @@ pub struct RubyProject {
     path: PathBuf,
 }
 
+// `spec.version = "1.2.3"` (or `s.version`, with any quote style and `.freeze`)
+const GEMSPEC_VERSION_PATTERN: &str = r#"(?m)^\s*\w+\.version\s*=\s*(['"])([^'"]+)['"]"#;
+// `spec.version = Foo::Bar::VERSION`
+const GEMSPEC_VERSION_CONSTANT_PATTERN: &str =
+    r"(?m)^\s*\w+\.version\s*=\s*((?:::)?[A-Z]\w*(?:::[A-Z]\w*)*)::VERSION\b";
+// `VERSION = "1.2.3"` in a version.rb file
+const VERSION_CONSTANT_PATTERN: &str = r#"(?m)^\s*VERSION\s*=\s*(['"])([^'"]+)['"]"#;
+
 impl RubyProject {
     pub fn new(path: PathBuf) -> Self {
         Self { path }
@@ -685,22 +1610,73 @@ impl RubyProject {
         None
     }
 
-    // Try to extract version from version.rb file
-    fn find_version_rb_file(&self) -> Option<PathBuf> {
-        let dir = self.path.parent().unwrap_or(Path::new("."));
+    // Get the gem name from the gemspec
+    fn find_gem_name(&self) -> Option<String> {
+        let content = fs::read_to_string(self.find_gemspec_file()?).ok()?;
+        let name_re = regex::Regex::new(r#"(?m)^\s*\w+\.name\s*=\s*["']([^"']+)["']"#).unwrap();
+        name_re.captures(&content).map(|caps| caps[1].to_string())
+    }
 
-        // First approach: try to find the project name from gemspec
+    // Find the file that defines the version constant. A gemspec that reads
+    // `Foo::VERSION` is followed to the file defining it; otherwise look for a
+    // conventional version.rb file.
+    fn find_version_rb_file(&self) -> Option<PathBuf> {
         if let Some(gemspec_path) = self.find_gemspec_file() {
             if let Ok(content) = fs::read_to_string(gemspec_path) {
-                // Try to extract gem name from gemspec
-                let name_re = regex::Regex::new(r#"[s\.]name\s*=\s*["']([^"']+)["']"#).unwrap();
-                if let Some(caps) = name_re.captures(&content) {
-                    if let Some(name) = caps.get(1) {
-                        let version_path = dir.join(name.as_str()).join("version.rb");
-                        if version_path.exists() {
-                            return Some(version_path);
-                        }
+                let constant_re = regex::Regex::new(GEMSPEC_VERSION_CONSTANT_PATTERN).unwrap();
+                if let Some(caps) = constant_re.captures(&content) {
+                    let namespace = caps[1].trim_start_matches("::").to_string();
+                    if let Some(path) = self.resolve_version_constant(&namespace) {
+                        return Some(path);
                     }
+                    warn!("Could not find the file defining {namespace}::VERSION");
+                }
+            }
+        }
+
+        self.find_conventional_version_rb_file()
+    }
+
+    // Find the file under lib/ that defines VERSION in the given namespace
+    fn resolve_version_constant(&self, namespace: &str) -> Option<PathBuf> {
+        let lib_dir = self.path.parent().unwrap_or(Path::new(".")).join("lib");
+        let version_re = regex::Regex::new(VERSION_CONSTANT_PATTERN).unwrap();
+        let defines_version =
+            |path: &Path| fs::read_to_string(path).is_ok_and(|c| version_re.is_match(&c));
+
+        // Conventional location: Foo::BarBaz → lib/foo/bar_baz/version.rb
+        let conventional: PathBuf = namespace.split("::").map(ruby_file_name).collect();
+        let conventional = lib_dir.join(conventional).join("version.rb");
+        if defines_version(&conventional) {
+            return Some(conventional);
+        }
+
+        // Otherwise, any file that opens the innermost module and defines VERSION
+        let innermost = namespace.rsplit("::").next()?;
+        let module_re = regex::Regex::new(&format!(
+            r"(?m)^\s*(?:module|class)\s+(?:\w+::)*{innermost}\b"
+        ))
+        .unwrap();
+        let mut ruby_files = Vec::new();
+        collect_files_with_extension(&lib_dir, "rb", &mut ruby_files);
+        ruby_files.into_iter().find(|path| {
+            fs::read_to_string(path)
+                .is_ok_and(|c| module_re.is_match(&c) && version_re.is_match(&c))
+        })
+    }
+
+    // Try to extract version from version.rb file
+    fn find_conventional_version_rb_file(&self) -> Option<PathBuf> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+
+        // First approach: try to find the project name from gemspec
+        if let Some(name) = self.find_gem_name() {
+            for version_path in [
+                dir.join("lib").join(&name).join("version.rb"),
+                dir.join(&name).join("version.rb"),
+            ] {
+                if version_path.exists() {
+                    return Some(version_path);
                 }
             }
         }
@@ -727,110 +1703,105 @@ impl RubyProject {
         None
     }
 
-    fn update_version_internal(&self, version: &Version, dry_run: bool) -> Result<String> {
-        // First check for gemspec file which usually contains the version
-        let gemspec_path = self.find_gemspec_file();
-        let version_rb_path = self.find_version_rb_file();
-
-        let old_version = self.get_version()?;
-        let mut diff = String::new();
-        let mut updated_any = false;
-
-        let prefix = if dry_run { "Would update" } else { "Updated" };
-        diff.push_str(&format!(
-            "{prefix} Ruby project version from {old_version} to {version}:\n"
-        ));
-
-        // Try to update gemspec
-        if let Some(ref path) = gemspec_path {
-            let content = fs::read_to_string(path).context("Failed to read gemspec file")?;
-
-            let version_re = regex::Regex::new(r#"(['\"])(\d+\.\d+\.\d+)(['\"]\s*)"#).unwrap();
-
-            if version_re.is_match(&content) {
-                diff.push_str(&format!("  File: {}\n", path.display()));
-
-                let new_content = version_re.replace(&content, |caps: &regex::Captures| {
-                    format!("{}{}{}", &caps[1], version, &caps[3])
-                });
-
-                if !dry_run && new_content != content {
-                    fs::write(path, new_content.as_bytes())
-                        .context("Failed to write updated gemspec file")?;
-                    updated_any = true;
-                }
+    // The version string literals to update: the gemspec's version assignment
+    // (unless it reads a constant) and the VERSION constant. Each is returned as
+    // the file and the byte range of the literal.
+    fn find_version_spans(&self) -> Result<Vec<(PathBuf, std::ops::Range<usize>)>> {
+        let mut spans = Vec::new();
+
+        if let Some(path) = self.find_gemspec_file() {
+            let content = read_file(&path)?;
+            let version_re = regex::Regex::new(GEMSPEC_VERSION_PATTERN).unwrap();
+            if let Some(value) = version_re.captures(&content).and_then(|caps| caps.get(2)) {
+                spans.push((path, value.range()));
             }
         }
 
-        // Try to update version.rb
-        if let Some(ref path) = version_rb_path {
-            let content = fs::read_to_string(path).context("Failed to read version.rb file")?;
-
-            let version_re = regex::Regex::new(r#"VERSION\s*=\s*['"]([\d\.]+)['"](.*)"#).unwrap();
+        if let Some(path) = self.find_version_rb_file() {
+            let content = read_file(&path)?;
+            let version_re = regex::Regex::new(VERSION_CONSTANT_PATTERN).unwrap();
+            if let Some(value) = version_re.captures(&content).and_then(|caps| caps.get(2)) {
+                spans.push((path, value.range()));
+            }
+        }
 
-            if version_re.is_match(&content) {
-                diff.push_str(&format!("  File: {}\n", path.display()));
+        Ok(spans)
+    }
 
-                let new_content = version_re.replace(&content, |caps: &regex::Captures| {
-                    format!(
-                        "VERSION = '{}'{}",
-                        version,
-                        if let Some(m) = caps.get(2) {
-                            m.as_str()
-                        } else {
-                            ""
-                        }
-                    )
-                });
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let spans = self.find_version_spans()?;
+        let mut changes = Vec::new();
+
+        // Only the version literal is replaced, so quote style and `.freeze`
+        // are kept
+        for (path, span) in &spans {
+            stage_change(&mut changes, path, |content| {
+                let mut new_content = content.to_string();
+                new_content.replace_range(span.clone(), &version.to_string());
+                new_content
+            })?;
+        }
 
-                if !dry_run && new_content != content {
-                    fs::write(path, new_content.as_bytes())
-                        .context("Failed to write updated version.rb file")?;
-                    updated_any = true;
-                }
+        // Refresh the gem's own entry in Gemfile.lock
+        let lock_path = self.path.with_file_name("Gemfile.lock");
+        if let (Some(name), true) = (self.find_gem_name(), lock_path.exists()) {
+            let content = read_file(&lock_path)?;
+            if let Some(new_content) = lockfile::update_gemfile_lock(&content, &name, version) {
+                changes.push(FileChange::new(lock_path, content, new_content));
             }
         }
 
-        if !updated_any && !dry_run {
+        if spans.is_empty() {
             warn!("Could not update Ruby project version. No version patterns were matched.");
-            diff.push_str("  No version patterns were matched in any files.");
         }
 
-        Ok(diff)
+        Ok(changes)
     }
 }
 
-impl Project for RubyProject {
-    fn get_version(&self) -> Result<Version> {
-        // Try to find version in gemspec
-        if let Some(gemspec_path) = self.find_gemspec_file() {
-            let content =
-                fs::read_to_string(gemspec_path).context("Failed to read gemspec file")?;
-
-            let version_re =
-                regex::Regex::new(r#"(?:version|s\.version)\s*=\s*['"]([^'"]+)['"]\s*"#).unwrap();
-
-            if let Some(caps) = version_re.captures(&content) {
-                if let Some(version_match) = caps.get(1) {
-                    return Version::parse(version_match.as_str())
-                        .context("Failed to parse version from gemspec");
-                }
+// Convert a Ruby constant name to its conventional file name (FooBar → foo_bar)
+fn ruby_file_name(constant: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in constant.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                name.push('_');
             }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
         }
+    }
+    name
+}
 
-        // Try to find version in version.rb
-        if let Some(version_rb_path) = self.find_version_rb_file() {
-            let content =
-                fs::read_to_string(version_rb_path).context("Failed to read version.rb file")?;
+// Recursively collect files with the given extension
+fn collect_files_with_extension(dir: &Path, extension: &str, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
 
-            let version_re = regex::Regex::new(r#"VERSION\s*=\s*['"]([^'"]+)['"]\s*"#).unwrap();
+    let mut paths: Vec<PathBuf> = entries.filter_map(Result::ok).map(|e| e.path()).collect();
+    paths.sort();
 
-            if let Some(caps) = version_re.captures(&content) {
-                if let Some(version_match) = caps.get(1) {
-                    return Version::parse(version_match.as_str())
-                        .context("Failed to parse version from version.rb");
-                }
-            }
+    for path in paths {
+        if path.is_dir() {
+            collect_files_with_extension(&path, extension, files);
+        } else if path.extension().is_some_and(|ext| ext == extension) {
+            files.push(path);
+        }
+    }
+}
+
+impl Project for RubyProject {
+    fn get_project_type(&self) -> &'static str {
+        "ruby"
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        if let Some((path, span)) = self.find_version_spans()?.into_iter().next() {
+            let content = read_file(&path)?;
+            return parse_version(&path, &content[span]);
         }
 
         warn!("Could not find version information in Ruby project files");
@@ -838,13 +1809,26 @@ impl Project for RubyProject {
         Ok(Version::new(0, 1, 0))
     }
 
-    fn update_version(&self, version: &Version) -> Result<()> {
-        self.update_version_internal(version, false)?;
-        Ok(())
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let mut locations = Vec::new();
+        for (path, span) in self.find_version_spans()? {
+            let content = read_file(&path)?;
+            let field = if path.extension().is_some_and(|ext| ext == "gemspec") {
+                "version"
+            } else {
+                "VERSION"
+            };
+            locations.push(VersionLocation {
+                field: field.to_string(),
+                value: content[span].to_string(),
+                path,
+            });
+        }
+        Ok(locations)
     }
 
-    fn dry_run_update(&self, version: &Version) -> Result<String> {
-        self.update_version_internal(version, true)
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
     }
 
     fn get_file_path(&self) -> &Path {
@@ -862,18 +1846,470 @@ impl Project for RubyProject {
             files.push(version_rb_path);
         }
 
+        let lock_path = self.path.with_file_name("Gemfile.lock");
+        if lock_path.exists() {
+            files.push(lock_path);
+        }
+
         files
     }
 
-    fn get_package_manager_update_command(&self) -> Option<String> {
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        vec![self.path.with_file_name("Gemfile.lock")]
+    }
+
+    fn get_lockfile_versions(&self) -> Result<Vec<VersionLocation>> {
+        let Some(name) = self.find_gem_name() else {
+            return Ok(Vec::new());
+        };
+        read_lockfile_versions(self.path.with_file_name("Gemfile.lock"), |content| {
+            Ok(lockfile::read_gemfile_lock(content, &name)
+                .map(|version| (name.clone(), version))
+                .into_iter()
+                .collect())
+        })
+    }
+
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
+        let lockfile = self.path.with_file_name("Gemfile.lock");
+
+        // Re-lock without installing; Bundler keeps the locked gem versions
+        if lockfile.exists() {
+            return Some(PackageManagerCommand::new("bundle", &["lock"]).with_lockfile(lockfile));
+        }
+
+        None
+    }
+}
+
+// Elixir project (mix.exs)
+pub struct MixProject {
+    path: PathBuf,
+}
+
+impl MixProject {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    // Locate the version string literal in mix.exs, following a `version: @attr`
+    // reference to the module attribute that defines it. Returns the byte range of
+    // the string contents and a description of where it was found.
+    fn find_version_span(&self, content: &str) -> Result<(std::ops::Range<usize>, String)> {
+        // Only look at the keyword list returned by `def project`, so that
+        // `version:` keys elsewhere in the file (e.g. in deps) are ignored
+        let project_start = regex::Regex::new(r"(?m)^\s*def\s+project\b")
+            .unwrap()
+            .find(content)
+            .map(|m| m.end())
+            .ok_or_else(|| anyhow!("No `def project` found in mix.exs"))?;
+
+        let version_re =
+            regex::Regex::new(r#"\bversion:\s*(?:"([^"]*)"|@([a-z_][a-zA-Z0-9_]*))"#).unwrap();
+        let caps = version_re
+            .captures(&content[project_start..])
+            .ok_or_else(|| Error::MissingVersion {
+                path: self.path.clone(),
+            })?;
+
+        if let Some(literal) = caps.get(1) {
+            let start = project_start + literal.start();
+            return Ok((start..start + literal.len(), "project.version".to_string()));
+        }
+
+        // Follow the module attribute, e.g. `@version "1.2.3"`
+        let attr = &caps[2];
+        let attr_re = regex::Regex::new(&format!(r#"(?m)^\s*@{attr}\s+"([^"]*)""#)).unwrap();
+        let literal = attr_re
+            .captures(content)
+            .and_then(|caps| caps.get(1))
+            .ok_or_else(|| anyhow!("Module attribute @{} not found in mix.exs", attr))?;
+
+        Ok((literal.range(), format!("@{attr}")))
+    }
+
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let content = read_file(&self.path)?;
+
+        let (span, _) = self.find_version_span(&content)?;
+
+        // Splice in the new version so the rest of the Elixir source is untouched
+        let mut new_content = content.clone();
+        new_content.replace_range(span, &version.to_string());
+
+        Ok(vec![FileChange::new(&self.path, content, new_content)])
+    }
+}
+
+impl Project for MixProject {
+    fn get_project_type(&self) -> &'static str {
+        "elixir"
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        let content = read_file(&self.path)?;
+
+        let (span, _) = self.find_version_span(&content)?;
+
+        parse_version(&self.path, &content[span])
+    }
+
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
+    }
+
+    fn get_file_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn get_files_to_commit(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    fn get_lockfiles(&self) -> Vec<PathBuf> {
+        vec![self.path.with_file_name("mix.lock")]
+    }
+
+    fn get_package_manager_update_command(&self) -> Option<PackageManagerCommand> {
+        // mix.lock doesn't record the project's own version, but fetching
+        // dependencies keeps it consistent with mix.exs
+        let command = PackageManagerCommand::new("mix", &["deps.get"])
+            .with_lockfile(self.path.with_file_name("mix.lock"));
+        Some(command)
+    }
+}
+
+// Xcode project (*.xcodeproj/project.pbxproj or Info.plist)
+pub struct XcodeProject {
+    path: PathBuf,
+}
+
+// Build settings in project.pbxproj look like `MARKETING_VERSION = 1.2.3;`, with the
+// value optionally quoted
+const MARKETING_VERSION_PATTERN: &str = r#"(?m)^(\s*MARKETING_VERSION\s*=\s*"?)([^";\s]+)("?\s*;)"#;
+const BUILD_NUMBER_PATTERN: &str =
+    r#"(?m)^(\s*CURRENT_PROJECT_VERSION\s*=\s*"?)([^";\s]+)("?\s*;)"#;
+const INFOPLIST_FILE_PATTERN: &str = r#"(?m)^\s*INFOPLIST_FILE\s*=\s*"?([^";]+?)"?\s*;"#;
+
+// Info.plist entries are a <key> followed by a <string> value
+const PLIST_VERSION_PATTERN: &str =
+    r"(<key>CFBundleShortVersionString</key>\s*<string>)([^<]*)(</string>)";
+const PLIST_BUILD_NUMBER_PATTERN: &str =
+    r"(<key>CFBundleVersion</key>\s*<string>)([^<]*)(</string>)";
+
+impl XcodeProject {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Find the project.pbxproj of the first *.xcodeproj bundle in a directory
+    pub fn find_pbxproj(dir: &Path) -> Option<PathBuf> {
+        let mut bundles: Vec<PathBuf> = fs::read_dir(dir)
+            .ok()?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "xcodeproj"))
+            .collect();
+        bundles.sort();
+
+        bundles
+            .into_iter()
+            .map(|bundle| bundle.join("project.pbxproj"))
+            .find(|path| path.exists())
+    }
+
+    fn is_plist(&self) -> bool {
+        self.path.extension().is_some_and(|ext| ext == "plist")
+    }
+
+    // Directory that paths in the build settings are relative to ($(SRCROOT))
+    fn source_root(&self) -> &Path {
         let dir = self.path.parent().unwrap_or(Path::new("."));
+        if self.is_plist() {
+            dir
+        } else {
+            dir.parent().unwrap_or(Path::new("."))
+        }
+    }
+
+    // Info.plist files whose versions need to be kept in sync. Plists that only
+    // reference $(MARKETING_VERSION) are left alone, since the build setting wins.
+    fn get_plist_files(&self) -> Vec<PathBuf> {
+        if self.is_plist() {
+            return vec![self.path.clone()];
+        }
+
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+
+        let re = regex::Regex::new(INFOPLIST_FILE_PATTERN).unwrap();
+        let mut files: Vec<PathBuf> = Vec::new();
+        for caps in re.captures_iter(&content) {
+            let relative = caps[1]
+                .trim_start_matches("$(SRCROOT)/")
+                .trim_start_matches("$(PROJECT_DIR)/");
+            let path = self.source_root().join(relative);
+            if path.exists() && !files.contains(&path) {
+                files.push(path);
+            }
+        }
 
-        // Check for Gemfile.lock to confirm Bundler is used
-        if dir.join("Gemfile.lock").exists() {
-            return Some("bundle install".to_string());
+        let version_re = regex::Regex::new(PLIST_VERSION_PATTERN).unwrap();
+        files.retain(|path| {
+            fs::read_to_string(path).is_ok_and(|content| {
+                version_re
+                    .captures(&content)
+                    .is_some_and(|caps| !caps[2].starts_with("$("))
+            })
+        });
+        files
+    }
+
+    // Build numbers currently in use, ignoring variable references and
+    // non-integer values
+    fn get_build_numbers(&self) -> Vec<u64> {
+        let mut numbers = Vec::new();
+        let build_re = regex::Regex::new(BUILD_NUMBER_PATTERN).unwrap();
+        let plist_build_re = regex::Regex::new(PLIST_BUILD_NUMBER_PATTERN).unwrap();
+
+        if !self.is_plist() {
+            if let Ok(content) = fs::read_to_string(&self.path) {
+                numbers.extend(
+                    build_re
+                        .captures_iter(&content)
+                        .filter_map(|caps| caps[2].parse::<u64>().ok()),
+                );
+            }
+        }
+        for path in self.get_plist_files() {
+            if let Ok(content) = fs::read_to_string(path) {
+                numbers.extend(
+                    plist_build_re
+                        .captures_iter(&content)
+                        .filter_map(|caps| caps[2].parse::<u64>().ok()),
+                );
+            }
+        }
+
+        numbers
+    }
+
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let version_str = version.to_string();
+
+        // Every configuration gets the same incremented build number
+        let old_build = self.get_build_numbers().into_iter().max();
+        let new_build = old_build.map(|n| (n + 1).to_string());
+
+        // Rewrites matches of `pattern`, skipping values that reference other
+        // build settings, and returns the new content and number of replacements
+        let rewrite = |content: &str, pattern: &str, value: &str| -> (String, usize) {
+            let re = regex::Regex::new(pattern).unwrap();
+            let mut count = 0;
+            let new_content = re.replace_all(content, |caps: &regex::Captures| {
+                if caps[2].starts_with("$(") {
+                    caps[0].to_string()
+                } else {
+                    count += 1;
+                    format!("{}{}{}", &caps[1], value, &caps[3])
+                }
+            });
+            (new_content.into_owned(), count)
+        };
+
+        let mut changes = Vec::new();
+
+        if !self.is_plist() {
+            let content = read_file(&self.path)?;
+            let (mut new_content, _) = rewrite(&content, MARKETING_VERSION_PATTERN, &version_str);
+            if let Some(new_build) = &new_build {
+                new_content = rewrite(&new_content, BUILD_NUMBER_PATTERN, new_build).0;
+            }
+            changes.push(FileChange::new(&self.path, content, new_content));
+        }
+
+        let plist_build_re = regex::Regex::new(PLIST_BUILD_NUMBER_PATTERN).unwrap();
+        for path in self.get_plist_files() {
+            let content = read_file(&path)?;
+            let (mut new_content, _) = rewrite(&content, PLIST_VERSION_PATTERN, &version_str);
+            if let Some(new_build) = &new_build {
+                // CFBundleVersion may also hold a dotted version rather than a
+                // build number; only integer build numbers are incremented
+                if plist_build_re
+                    .captures(&new_content)
+                    .is_some_and(|caps| caps[2].parse::<u64>().is_ok())
+                {
+                    new_content = rewrite(&new_content, PLIST_BUILD_NUMBER_PATTERN, new_build).0;
+                }
+            }
+            changes.push(FileChange::new(path, content, new_content));
+        }
+
+        Ok(changes)
+    }
+}
+
+// Marketing versions are often just `1.2`; treat missing components as zero
+fn parse_marketing_version(path: &Path, version_str: &str) -> Result<Version> {
+    let mut parts: Vec<&str> = version_str.split('.').collect();
+    if parts.len() < 3 && parts.iter().all(|p| p.parse::<u64>().is_ok()) {
+        parts.resize(3, "0");
+        return parse_version(path, &parts.join("."));
+    }
+    parse_version(path, version_str)
+}
+
+impl Project for XcodeProject {
+    fn get_project_type(&self) -> &'static str {
+        "xcode"
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        if !self.is_plist() {
+            let content = read_file(&self.path)?;
+            let re = regex::Regex::new(MARKETING_VERSION_PATTERN).unwrap();
+            let marketing_version = re
+                .captures_iter(&content)
+                .map(|caps| caps[2].to_string())
+                .find(|value| !value.starts_with("$("));
+            if let Some(marketing_version) = marketing_version {
+                return parse_marketing_version(&self.path, &marketing_version);
+            }
+        }
+
+        // Older projects keep the version in Info.plist only
+        let re = regex::Regex::new(PLIST_VERSION_PATTERN).unwrap();
+        for path in self.get_plist_files() {
+            let content = read_file(&path)?;
+            if let Some(caps) = re.captures(&content) {
+                return parse_marketing_version(&path, &caps[2]);
+            }
+        }
+
+        Err(Error::MissingVersion {
+            path: self.path.clone(),
+        })
+    }
+
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
+    }
+
+    fn get_file_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn get_files_to_commit(&self) -> Vec<PathBuf> {
+        let mut files = vec![self.path.clone()];
+        for path in self.get_plist_files() {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+        files
+    }
+}
+
+// Plain version file project (VERSION or version.txt)
+pub struct VersionFileProject {
+    path: PathBuf,
+}
+
+impl VersionFileProject {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn compute_changes(&self, version: &Version) -> Result<Vec<FileChange>> {
+        let content = read_file(&self.path)?;
+
+        // Keep a `v` prefix and whatever line ending the file had
+        let trailing = &content[content.trim_end().len()..];
+        let prefix = if content.trim().starts_with('v') {
+            "v"
+        } else {
+            ""
+        };
+        let new_content = format!("{prefix}{version}{trailing}");
+
+        Ok(vec![FileChange::new(&self.path, content, new_content)])
+    }
+}
+
+impl Project for VersionFileProject {
+    fn get_project_type(&self) -> &'static str {
+        "version-file"
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        let content = read_file(&self.path)?;
+
+        let version_str = content.trim();
+        let version_str = version_str.strip_prefix('v').unwrap_or(version_str);
+
+        parse_version(&self.path, version_str)
+    }
+
+    fn dry_run_update(&self, version: &Version) -> Result<Vec<FileChange>> {
+        self.compute_changes(version)
+    }
+
+    fn get_file_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn get_files_to_commit(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+// Repository without a manifest, versioned by its git tags (vX.Y.Z)
+pub struct GitTagProject {
+    dir: PathBuf,
+}
+
+impl GitTagProject {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+impl Project for GitTagProject {
+    fn get_project_type(&self) -> &'static str {
+        "git-tag"
+    }
+
+    fn get_version(&self) -> Result<Version> {
+        if let Some((tag, version)) = git::latest_version_tag(&self.dir, "") {
+            debug!("Using version from git tag {tag}");
+            return Ok(version);
         }
 
-        // Default to bundle install if Gemfile exists
-        Some("bundle install".to_string())
+        warn!("No vX.Y.Z tag found; assuming no release yet");
+        Ok(Version::new(0, 0, 0))
+    }
+
+    fn get_version_locations(&self) -> Result<Vec<VersionLocation>> {
+        let locations =
+            git::latest_version_tag(&self.dir, "").map(|(tag, version)| VersionLocation {
+                path: self.dir.clone(),
+                field: format!("tag {tag}"),
+                value: version.to_string(),
+            });
+        Ok(locations.into_iter().collect())
+    }
+
+    fn dry_run_update(&self, _version: &Version) -> Result<Vec<FileChange>> {
+        // There are no files to update; the release is recorded by the tag
+        Ok(Vec::new())
+    }
+
+    fn get_file_path(&self) -> &Path {
+        &self.dir
+    }
+
+    fn get_files_to_commit(&self) -> Vec<PathBuf> {
+        Vec::new()
     }
 }
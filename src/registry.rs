@@ -0,0 +1,259 @@
+//! The project types that can be detected in a directory
+//!
+//! Each project type is a [`ProjectDetector`] with a name and a priority.
+//! [`ProjectRegistry::default`] holds the built-in types; library users can
+//! register their own alongside them:
+//!
+//! ```no_run
+//! use project_version::project::{Project, VersionFileProject};
+//! use project_version::registry::{FnDetector, ProjectRegistry};
+//! use std::path::Path;
+//!
+//! // Try a RELEASE file before the built-in VERSION and version.txt files
+//! let mut registry = ProjectRegistry::default();
+//! registry.register(FnDetector::new("release-file", 15, "RELEASE", |dir: &Path| {
+//!     let path = dir.join("RELEASE");
+//!     Ok(path
+//!         .exists()
+//!         .then(|| Box::new(VersionFileProject::new(path)) as Box<dyn Project>))
+//! }));
+//! let project = registry.detect(Path::new("."))?;
+//! # Ok::<(), project_version::error::Error>(())
+//! ```
+
+use crate::config::{self, ProjectTypeConfig};
+use crate::custom::CustomDetector;
+use crate::error::{Error, Result};
+use crate::git;
+use crate::project::{
+    GitTagProject, GoProject, HelmChartProject, MixProject, NodeProject, Project, PythonProject,
+    RubyProject, RustProject, SetuptoolsProject, VersionFileProject, XcodeProject,
+};
+use log::debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Recognizes one type of project. Detectors are shared between threads with
+/// the registry.
+pub trait ProjectDetector: Send + Sync {
+    /// The name of the project type, as passed to `--type`
+    fn name(&self) -> &str;
+
+    /// Detectors with a higher priority are tried first
+    fn priority(&self) -> i32;
+
+    /// The files the detector looks for
+    fn description(&self) -> &str;
+
+    /// Get the project in `dir`, or `None` if it isn't this type of project
+    fn detect(&self, dir: &Path) -> Result<Option<Box<dyn Project>>>;
+}
+
+type DetectFn = dyn Fn(&Path) -> Result<Option<Box<dyn Project>>> + Send + Sync;
+
+/// A detector that calls a function
+pub struct FnDetector {
+    name: String,
+    priority: i32,
+    description: String,
+    detect: Box<DetectFn>,
+}
+
+impl FnDetector {
+    pub fn new(
+        name: impl Into<String>,
+        priority: i32,
+        description: impl Into<String>,
+        detect: impl Fn(&Path) -> Result<Option<Box<dyn Project>>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            description: description.into(),
+            detect: Box::new(detect),
+        }
+    }
+}
+
+impl ProjectDetector for FnDetector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn detect(&self, dir: &Path) -> Result<Option<Box<dyn Project>>> {
+        (self.detect)(dir)
+    }
+}
+
+/// The detectors to try, from the highest priority down
+#[derive(Clone)]
+pub struct ProjectRegistry {
+    detectors: Vec<Arc<dyn ProjectDetector>>,
+}
+
+impl ProjectRegistry {
+    /// A registry with no project types
+    pub fn empty() -> Self {
+        Self {
+            detectors: Vec::new(),
+        }
+    }
+
+    /// Add a detector. It is tried after any registered detectors with the
+    /// same priority.
+    pub fn register(&mut self, detector: impl ProjectDetector + 'static) -> &mut Self {
+        let index = self
+            .detectors
+            .partition_point(|d| d.priority() >= detector.priority());
+        self.detectors.insert(index, Arc::new(detector));
+        self
+    }
+
+    /// Add the project types defined in the configuration (`[[types]]`)
+    pub fn register_config_types(&mut self, types: &[ProjectTypeConfig]) -> Result<&mut Self> {
+        for project_type in types {
+            self.register(CustomDetector::new(project_type)?);
+        }
+        Ok(self)
+    }
+
+    /// The registered detectors, in the order they are tried
+    pub fn detectors(&self) -> impl Iterator<Item = &dyn ProjectDetector> {
+        self.detectors.iter().map(|d| d.as_ref())
+    }
+
+    /// Get the detector for a project type
+    pub fn get(&self, name: &str) -> Option<&dyn ProjectDetector> {
+        self.detectors().find(|d| d.name() == name)
+    }
+
+    /// Detect the project in `dir` with the first detector that recognizes it
+    pub fn detect(&self, dir: &Path) -> Result<Box<dyn Project>> {
+        for detector in self.detectors() {
+            if let Some(project) = detector.detect(dir)? {
+                debug!(
+                    "Detected {} project ({})",
+                    detector.name(),
+                    project.get_file_path().display()
+                );
+                return Ok(project);
+            }
+        }
+        Err(Error::NoProject {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Detect the project in `dir` as the named type, instead of the type
+    /// auto-detection would pick
+    pub fn detect_as(&self, dir: &Path, name: &str) -> Result<Box<dyn Project>> {
+        let detector = self.get(name).ok_or_else(|| Error::UnknownProjectType {
+            name: name.to_string(),
+        })?;
+        detector
+            .detect(dir)?
+            .ok_or_else(|| Error::ProjectTypeNotFound {
+                name: name.to_string(),
+                dir: dir.to_path_buf(),
+            })
+    }
+}
+
+/// The built-in project types
+impl Default for ProjectRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(FnDetector::new(
+                "node",
+                100,
+                "package.json, package.json5",
+                |dir| {
+                    Ok(["package.json", "package.json5"]
+                        .into_iter()
+                        .map(|file_name| dir.join(file_name))
+                        .find(|path| path.exists())
+                        .map(|path| Box::new(NodeProject::new(path)) as Box<dyn Project>))
+                },
+            ))
+            .register(FnDetector::new("python", 90, "pyproject.toml", |dir| {
+                Ok(existing(dir, "pyproject.toml")
+                    .map(|path| Box::new(PythonProject::new(path)) as Box<dyn Project>))
+            }))
+            .register(FnDetector::new(
+                "setuptools",
+                80,
+                "setup.cfg, setup.py",
+                |dir| {
+                    Ok(SetuptoolsProject::find_setup_file(dir)
+                        .map(|path| Box::new(SetuptoolsProject::new(path)) as Box<dyn Project>))
+                },
+            ))
+            .register(FnDetector::new("rust", 70, "Cargo.toml", |dir| {
+                Ok(existing(dir, "Cargo.toml")
+                    .map(|path| Box::new(RustProject::new(path)) as Box<dyn Project>))
+            }))
+            .register(FnDetector::new("go", 60, "go.mod", |dir| {
+                Ok(existing(dir, "go.mod")
+                    .map(|path| Box::new(GoProject::new(path)) as Box<dyn Project>))
+            }))
+            .register(FnDetector::new("ruby", 50, "Gemfile", |dir| {
+                Ok(existing(dir, "Gemfile")
+                    .map(|path| Box::new(RubyProject::new(path)) as Box<dyn Project>))
+            }))
+            .register(FnDetector::new("elixir", 40, "mix.exs", |dir| {
+                Ok(existing(dir, "mix.exs")
+                    .map(|path| Box::new(MixProject::new(path)) as Box<dyn Project>))
+            }))
+            .register(FnDetector::new("helm", 30, "Chart.yaml", |dir| {
+                let Some(path) = existing(dir, "Chart.yaml") else {
+                    return Ok(None);
+                };
+                let config = config::load_config(&dir.to_string_lossy())?;
+                Ok(Some(Box::new(
+                    HelmChartProject::new(path).with_options(config.helm),
+                )))
+            }))
+            .register(FnDetector::new(
+                "xcode",
+                20,
+                "*.xcodeproj/project.pbxproj, Info.plist",
+                |dir| {
+                    Ok(XcodeProject::find_pbxproj(dir)
+                        .or_else(|| existing(dir, "Info.plist"))
+                        .map(|path| Box::new(XcodeProject::new(path)) as Box<dyn Project>))
+                },
+            ))
+            .register(FnDetector::new(
+                "version-file",
+                10,
+                "VERSION, version.txt",
+                |dir| {
+                    Ok(["VERSION", "version.txt"]
+                        .into_iter()
+                        .find_map(|file_name| existing(dir, file_name))
+                        .map(|path| Box::new(VersionFileProject::new(path)) as Box<dyn Project>))
+                },
+            ))
+            // Versions the repository by its tags alone, when nothing else matches
+            .register(FnDetector::new("git-tag", 0, "a git repository", |dir| {
+                Ok(git::repo_root(dir)
+                    .map(|_| Box::new(GitTagProject::new(dir.to_path_buf())) as Box<dyn Project>))
+            }));
+        registry
+    }
+}
+
+// The path of `file_name` in `dir`, if it exists
+fn existing(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    let path = dir.join(file_name);
+    path.exists().then_some(path)
+}
@@ -0,0 +1,581 @@
+//! Planning and carrying out a release
+//!
+//! `bump` and `set` both compute a [`ReleasePlan`] before anything is written:
+//! the new version, the proposed edit to every file, the lock file update, and
+//! the release commit and tag. A dry run only shows the plan; otherwise
+//! [`ReleasePlan::execute`] carries it out, putting the original files back and
+//! undoing the release commit if a step fails.
+//!
+//! [`Releaser`] wraps both steps for use as a library: it reports progress
+//! through a [`ReleaseObserver`] rather than printing, and never prompts.
+
+use crate::bump::{self, BumpType};
+use crate::changelog;
+use crate::config::{self, FileRule};
+use crate::diff::{self, FileChange};
+use crate::error::{Error, Result};
+use crate::file_rules;
+use crate::git;
+use crate::lockfile::LockfileHashes;
+use crate::project::{PackageManagerCommand, Project};
+use crate::registry::ProjectRegistry;
+use crate::snapshot::Snapshot;
+use anyhow::Context;
+use semver::Version;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a package manager may take to update the lock files
+pub const PACKAGE_MANAGER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What a release does besides updating the project files
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseOptions {
+    /// Skip committing changes, and so tagging
+    pub no_commit: bool,
+    /// Skip tagging the commit
+    pub no_tag: bool,
+    /// Skip updating lock files
+    pub no_lockupdate: bool,
+    /// Update the project's own lock file entries instead of running the
+    /// package manager
+    pub offline: bool,
+    /// Replace the release tag if it already exists
+    pub force_tag: bool,
+    /// Additional files to update, from the configuration file
+    pub files: Vec<FileRule>,
+}
+
+/// How a release brings the lock files up to date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockfileUpdate {
+    /// The project has no package manager
+    None,
+    /// The project's own lock file entries are rewritten directly; the edits
+    /// are part of the plan's changes
+    Offline,
+    /// The package manager is run once the files are written
+    Run(PackageManagerCommand),
+    /// The package manager would be run, but lock file updates were skipped
+    Skip(PackageManagerCommand),
+}
+
+/// The tag a release creates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagPlan {
+    pub name: String,
+    /// Whether a tag with this name already exists
+    pub exists: bool,
+    /// Whether to replace an existing tag; an existing tag that isn't
+    /// replaced is left alone
+    pub force: bool,
+}
+
+/// Everything a release will do, computed before anything is written
+#[derive(Debug, Clone)]
+pub struct ReleasePlan {
+    pub old_version: Version,
+    pub new_version: Version,
+    /// Proposed edits, in the order they are written: the project files, the
+    /// configured files, the changelog and, offline, the lock files
+    pub changes: Vec<FileChange>,
+    /// The changelog, if the project has one
+    pub changelog: Option<PathBuf>,
+    pub lockfile_update: LockfileUpdate,
+    /// Message of the release commit, if one is made
+    pub commit_message: Option<String>,
+    /// The release tag, if one is created
+    pub tag: Option<TagPlan>,
+    // The project directory, where git runs
+    dir: PathBuf,
+    // Where the package manager runs
+    package_dir: PathBuf,
+    // Lock files the package manager may change
+    lockfiles: Vec<PathBuf>,
+}
+
+/// The result of carrying out a release plan
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseOutcome {
+    /// Files that were changed, which are committed with the release
+    pub files: Vec<PathBuf>,
+    /// Output of the package manager, if it was run
+    pub package_manager_output: Option<String>,
+    /// SHA of the release commit, if one was made
+    pub commit: Option<String>,
+    /// Name of the release tag, if one was created
+    pub tag: Option<String>,
+}
+
+impl ReleasePlan {
+    /// Plan a release of the project in `dir` from `old_version` to
+    /// `new_version`
+    pub fn new(
+        project: &dyn Project,
+        dir: &Path,
+        old_version: Version,
+        new_version: Version,
+        options: &ReleaseOptions,
+    ) -> Result<Self> {
+        let mut changes = project.dry_run_update(&new_version)?;
+        for change in
+            file_rules::compute_changes(dir, &options.files, &old_version, &new_version, &changes)?
+        {
+            diff::merge(&mut changes, change);
+        }
+
+        let changelog = changelog::find_changelog(&dir.to_string_lossy());
+        if let Some(path) = &changelog {
+            changes.extend(changelog::dry_run_update_changelog(path, &new_version)?);
+        }
+
+        let lockfile_update = match project.get_package_manager_update_command() {
+            _ if options.offline => {
+                changes.extend(project.dry_run_update_lockfiles(&new_version)?);
+                LockfileUpdate::Offline
+            }
+            Some(command) if options.no_lockupdate => LockfileUpdate::Skip(command),
+            Some(command) => LockfileUpdate::Run(command),
+            None => LockfileUpdate::None,
+        };
+
+        let commit_message =
+            (!options.no_commit).then(|| format!("release: version {new_version}"));
+
+        let tag = if options.no_commit || options.no_tag {
+            None
+        } else {
+            let name = project.get_tag_name(&new_version);
+            Some(TagPlan {
+                exists: git::tag_exists(dir, &name)?,
+                name,
+                force: options.force_tag,
+            })
+        };
+
+        Ok(Self {
+            old_version,
+            new_version,
+            changes,
+            changelog,
+            lockfile_update,
+            commit_message,
+            tag,
+            dir: dir.to_path_buf(),
+            package_dir: project
+                .get_file_path()
+                .parent()
+                .unwrap_or(Path::new("."))
+                .to_path_buf(),
+            lockfiles: project.get_lockfiles(),
+        })
+    }
+
+    /// The proposed edits that change a file's content
+    pub fn changed_files(&self) -> impl Iterator<Item = &FileChange> {
+        self.changes.iter().filter(|change| change.is_changed())
+    }
+
+    /// The proposed edits to lock files
+    pub fn lockfile_changes(&self) -> impl Iterator<Item = &FileChange> {
+        self.changed_files()
+            .filter(|change| self.lockfiles.contains(&change.path))
+    }
+
+    /// The proposed edit to the changelog, if it has an unreleased section
+    pub fn changelog_change(&self) -> Option<&FileChange> {
+        let changelog = self.changelog.as_ref()?;
+        self.changed_files()
+            .find(|change| change.path == *changelog)
+    }
+
+    /// The files the release changes, which are committed with it, not
+    /// counting lock files the package manager changes
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for change in self.changed_files() {
+            if !files.contains(&change.path) {
+                files.push(change.path.clone());
+            }
+        }
+        files
+    }
+
+    /// Carry out the plan: write the files, update the lock files, then commit
+    /// and tag, reporting each step to `observer`. If any step fails, the
+    /// original files are restored and the release commit is undone.
+    pub fn execute(&self, observer: &mut dyn ReleaseObserver) -> Result<ReleaseOutcome> {
+        // Remember every file the release may change, so that they can be
+        // restored if a later step fails
+        let mut paths = self.files();
+        paths.extend(self.lockfiles.iter().cloned());
+        let snapshot = Snapshot::capture(paths)?;
+        let previous_head = self
+            .commit_message
+            .as_ref()
+            .and_then(|_| git::head_sha(&self.dir).ok());
+
+        let result = self
+            .update_files(observer)
+            .and_then(|outcome| self.commit_and_tag(outcome, observer));
+        match result {
+            Ok(outcome) => Ok(outcome),
+            Err(e) => {
+                // Put the files back even if the commit can't be undone, and
+                // keep the original error with any rollback failures
+                let restored = snapshot.restore();
+                let reset = match &self.commit_message {
+                    Some(_) => git::reset(&self.dir, previous_head.as_deref()),
+                    None => Ok(()),
+                };
+                let mut error = anyhow::Error::from(e);
+                if let Err(reset_error) = &reset {
+                    error =
+                        error.context(format!("Failed to undo the release commit: {reset_error}"));
+                }
+                error = match restored {
+                    Ok(()) => {
+                        if reset.is_ok() {
+                            observer.on_event(&ReleaseEvent::RolledBack);
+                        }
+                        error.context("Release failed; restored the original files")
+                    }
+                    Err(restore_error) => error.context(format!(
+                        "Release failed, and restoring the original files failed: {restore_error:#}"
+                    )),
+                };
+                Err(error.into())
+            }
+        }
+    }
+
+    // Commit the changed files and tag the commit
+    fn commit_and_tag(
+        &self,
+        mut outcome: ReleaseOutcome,
+        observer: &mut dyn ReleaseObserver,
+    ) -> Result<ReleaseOutcome> {
+        let Some(message) = &self.commit_message else {
+            return Ok(outcome);
+        };
+
+        if git::commit_changes(&self.dir, &outcome.files, message)? {
+            let sha = git::head_sha(&self.dir)?;
+            observer.on_event(&ReleaseEvent::Committed(sha.clone()));
+            outcome.commit = Some(sha);
+        }
+
+        if let Some(tag) = &self.tag {
+            if tag.exists && !tag.force {
+                observer.on_event(&ReleaseEvent::TagSkipped(tag.name.clone()));
+            } else {
+                git::create_tag(&self.dir, &tag.name, tag.exists)?;
+                observer.on_event(&ReleaseEvent::Tagged {
+                    name: tag.name.clone(),
+                    replaced: tag.exists,
+                });
+                outcome.tag = Some(tag.name.clone());
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    // Write the proposed edits and run the package manager
+    fn update_files(&self, observer: &mut dyn ReleaseObserver) -> Result<ReleaseOutcome> {
+        for change in &self.changes {
+            change.apply()?;
+            if change.is_changed() {
+                observer.on_event(&ReleaseEvent::FileUpdated(change.path.clone()));
+            }
+        }
+
+        let mut outcome = ReleaseOutcome {
+            files: self.files(),
+            ..ReleaseOutcome::default()
+        };
+
+        if let LockfileUpdate::Run(command) = &self.lockfile_update {
+            observer.on_event(&ReleaseEvent::UpdatingDependencies(command.clone()));
+
+            // Commit exactly the lock files the package manager changes
+            let lockfile_hashes = LockfileHashes::capture(&self.lockfiles);
+            let output = command
+                .run(&self.package_dir, PACKAGE_MANAGER_TIMEOUT)
+                .context("Failed to update dependencies")?;
+            for path in lockfile_hashes.changed() {
+                if !outcome.files.contains(&path) {
+                    outcome.files.push(path);
+                }
+            }
+            observer.on_event(&ReleaseEvent::DependenciesUpdated(output.clone()));
+            outcome.package_manager_output = Some(output);
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// A step of a release, reported to a [`ReleaseObserver`] as it happens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseEvent {
+    /// A file was written with new content
+    FileUpdated(PathBuf),
+    /// The package manager is about to run
+    UpdatingDependencies(PackageManagerCommand),
+    /// The package manager finished, with its output
+    DependenciesUpdated(String),
+    /// The release commit was made, with its SHA
+    Committed(String),
+    /// The release tag was created, replacing an existing tag if `replaced`
+    Tagged { name: String, replaced: bool },
+    /// The release tag already exists and was left alone
+    TagSkipped(String),
+    /// A step failed, so the original files were restored and the release
+    /// commit undone
+    RolledBack,
+}
+
+/// Receives the progress of a release. Closures taking a `&ReleaseEvent` are
+/// observers.
+pub trait ReleaseObserver {
+    fn on_event(&mut self, event: &ReleaseEvent);
+}
+
+impl<F: FnMut(&ReleaseEvent)> ReleaseObserver for F {
+    fn on_event(&mut self, event: &ReleaseEvent) {
+        self(event)
+    }
+}
+
+/// The result of a release, or of a dry run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReleaseReport {
+    pub old_version: String,
+    pub new_version: String,
+    pub dry_run: bool,
+    /// Files that were (or, in a dry run, would be) changed
+    pub files: Vec<PathBuf>,
+    /// SHA of the release commit, if one was made
+    pub commit: Option<String>,
+    /// Name of the release tag, if one was created
+    pub tag: Option<String>,
+}
+
+impl ReleaseReport {
+    /// Report a release that was carried out
+    pub fn new(plan: &ReleasePlan, outcome: ReleaseOutcome) -> Self {
+        Self {
+            old_version: plan.old_version.to_string(),
+            new_version: plan.new_version.to_string(),
+            dry_run: false,
+            files: outcome.files,
+            commit: outcome.commit,
+            tag: outcome.tag,
+        }
+    }
+
+    /// Report what a release would do
+    pub fn dry_run(plan: &ReleasePlan) -> Self {
+        Self {
+            old_version: plan.old_version.to_string(),
+            new_version: plan.new_version.to_string(),
+            dry_run: true,
+            files: plan.files(),
+            commit: None,
+            tag: None,
+        }
+    }
+}
+
+/// The version a release moves to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Bump the current version
+    Bump(BumpType),
+    /// Set a specific version
+    Set(Version),
+}
+
+/// Runs a release of the project in a directory, without printing or
+/// prompting. An existing release tag is left alone unless
+/// [`force_tag`](Releaser::force_tag) is set. A `Releaser` is `Send`, so it can
+/// run on another thread.
+///
+/// ```no_run
+/// use project_version::bump::BumpType;
+/// use project_version::release::{ReleaseEvent, Releaser};
+///
+/// let report = Releaser::new(".")
+///     .bump(BumpType::Minor)
+///     .dry_run(true)
+///     .observer(|event: &ReleaseEvent| eprintln!("{event:?}"))
+///     .run()?;
+/// println!("{} → {}", report.old_version, report.new_version);
+/// # Ok::<(), project_version::error::Error>(())
+/// ```
+pub struct Releaser {
+    dir: PathBuf,
+    target: Target,
+    dry_run: bool,
+    force: bool,
+    options: ReleaseOptions,
+    // Whether to read the file rules from the configuration file
+    load_files: bool,
+    registry: ProjectRegistry,
+    project_type: Option<String>,
+    observer: Option<Box<dyn ReleaseObserver + Send>>,
+}
+
+impl Releaser {
+    /// Release the project in `dir`, by default with a patch bump, a commit
+    /// and a tag
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            target: Target::Bump(BumpType::Patch),
+            dry_run: false,
+            force: false,
+            options: ReleaseOptions::default(),
+            load_files: true,
+            registry: ProjectRegistry::default(),
+            project_type: None,
+            observer: None,
+        }
+    }
+
+    /// Bump the current version
+    pub fn bump(mut self, bump_type: BumpType) -> Self {
+        self.target = Target::Bump(bump_type);
+        self
+    }
+
+    /// Set a specific version
+    pub fn set(mut self, version: Version) -> Self {
+        self.target = Target::Set(version);
+        self
+    }
+
+    /// Plan the release without making changes
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Allow setting a version lower than the current one
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Whether to commit the changes
+    pub fn commit(mut self, commit: bool) -> Self {
+        self.options.no_commit = !commit;
+        self
+    }
+
+    /// Whether to tag the release commit
+    pub fn tag(mut self, tag: bool) -> Self {
+        self.options.no_tag = !tag;
+        self
+    }
+
+    /// Replace the release tag if it already exists
+    pub fn force_tag(mut self, force_tag: bool) -> Self {
+        self.options.force_tag = force_tag;
+        self
+    }
+
+    /// Whether to update lock files
+    pub fn update_lockfiles(mut self, update: bool) -> Self {
+        self.options.no_lockupdate = !update;
+        self
+    }
+
+    /// Update the project's own lock file entries instead of running the
+    /// package manager
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.options.offline = offline;
+        self
+    }
+
+    /// Use these file rules instead of the ones in the configuration file
+    pub fn files(mut self, files: Vec<FileRule>) -> Self {
+        self.options.files = files;
+        self.load_files = false;
+        self
+    }
+
+    /// Detect the project with these project types instead of the built-in
+    /// ones
+    pub fn registry(mut self, registry: ProjectRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Treat the project as this type instead of detecting its type
+    pub fn project_type(mut self, name: impl Into<String>) -> Self {
+        self.project_type = Some(name.into());
+        self
+    }
+
+    /// Report the progress of the release to `observer`
+    pub fn observer(mut self, observer: impl ReleaseObserver + Send + 'static) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Plan the release of `project`
+    pub fn plan_for(&self, project: &dyn Project) -> Result<ReleasePlan> {
+        let old_version = project.get_version()?;
+        let new_version = match &self.target {
+            Target::Bump(bump_type) => {
+                bump::next_version(project, &self.dir, &old_version, *bump_type)?
+            }
+            Target::Set(version) => {
+                if !self.force && *version < old_version {
+                    return Err(Error::VersionNotIncreasing {
+                        current: old_version,
+                        new: version.clone(),
+                    });
+                }
+                version.clone()
+            }
+        };
+
+        let mut options = self.options.clone();
+        if self.load_files {
+            options.files = config::load_config(&self.dir.to_string_lossy())?.files;
+        }
+
+        ReleasePlan::new(project, &self.dir, old_version, new_version, &options)
+    }
+
+    /// Detect the project and plan its release
+    pub fn plan(&self) -> Result<ReleasePlan> {
+        let mut registry = self.registry.clone();
+        registry.register_config_types(&config::load_config(&self.dir.to_string_lossy())?.types)?;
+        let project = match &self.project_type {
+            Some(name) => registry.detect_as(&self.dir, name)?,
+            None => registry.detect(&self.dir)?,
+        };
+        self.plan_for(project.as_ref())
+    }
+
+    /// Plan the release and, unless this is a dry run, carry it out
+    pub fn run(mut self) -> Result<ReleaseReport> {
+        let plan = self.plan()?;
+        if self.dry_run {
+            return Ok(ReleaseReport::dry_run(&plan));
+        }
+
+        let mut ignore = |_: &ReleaseEvent| {};
+        let observer: &mut dyn ReleaseObserver = match &mut self.observer {
+            Some(observer) => observer.as_mut(),
+            None => &mut ignore,
+        };
+        let outcome = plan.execute(observer)?;
+        Ok(ReleaseReport::new(&plan, outcome))
+    }
+}
@@ -0,0 +1,52 @@
+//! Rolling back a release that fails partway through
+//!
+//! A snapshot records the contents of the files a release may change before
+//! anything is written, so that they can be put back if a later step fails.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// The original contents of a set of files
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    // `None` for files that didn't exist
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl Snapshot {
+    /// Record the current contents of `paths`
+    pub fn capture(paths: impl IntoIterator<Item = PathBuf>) -> Result<Self> {
+        let mut files: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+        for path in paths {
+            if files.iter().any(|(existing, _)| *existing == path) {
+                continue;
+            }
+            let content = if path.exists() {
+                Some(
+                    fs::read(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?,
+                )
+            } else {
+                None
+            };
+            files.push((path, content));
+        }
+        Ok(Self { files })
+    }
+
+    /// Put every file back as it was when the snapshot was taken, removing
+    /// files that didn't exist then
+    pub fn restore(&self) -> Result<()> {
+        for (path, content) in &self.files {
+            match content {
+                Some(content) => fs::write(path, content)
+                    .with_context(|| format!("Failed to restore {}", path.display()))?,
+                None if path.exists() => fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
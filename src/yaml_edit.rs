@@ -0,0 +1,299 @@
+//! Formatting-preserving edits of scalar values in YAML documents
+//!
+//! serde_yaml doesn't preserve comments, quoting or layout, so edits are made by
+//! locating the exact text of a scalar node in the block structure of the
+//! document and splicing in the new value. The located node is checked against
+//! the parsed document before anything is changed.
+
+use anyhow::{anyhow, Context, Result};
+use std::ops::Range;
+
+/// A step in the path from the document root to a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// A key of a mapping
+    Key(&'a str),
+    /// An item of a sequence
+    Index(usize),
+}
+
+/// How a scalar is written in the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarStyle {
+    Plain,
+    SingleQuoted,
+    DoubleQuoted,
+}
+
+/// The location of a scalar value in a YAML document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScalarSpan {
+    /// Byte range of the value, excluding any quotes
+    pub range: Range<usize>,
+    /// Quoting style of the value
+    pub style: ScalarStyle,
+    /// The value, with quoting escapes resolved
+    pub value: String,
+}
+
+// A significant (non-blank, non-comment) line, or the remainder of a sequence
+// item's line after its `- ` indicator
+#[derive(Debug, Clone, Copy)]
+struct Line<'a> {
+    indent: usize,
+    start: usize,
+    text: &'a str,
+}
+
+/// Find the scalar at `path` in a YAML document
+///
+/// Returns `Ok(None)` if the document has no scalar at that path, and an error
+/// if the document can't be parsed or the node is written in a form that can't
+/// be edited in place (flow collections, block scalars, anchors, etc.).
+pub fn find_scalar(content: &str, path: &[Segment]) -> Result<Option<ScalarSpan>> {
+    let document: serde_yaml::Value =
+        serde_yaml::from_str(content).context("Failed to parse YAML")?;
+
+    let Some(parsed) = get_node(&document, path) else {
+        return Ok(None);
+    };
+
+    let lines = significant_lines(content);
+    let span = find_in_block(&lines, path)
+        .ok_or_else(|| anyhow!("Cannot edit {} in place", describe_path(path)))?;
+
+    // Make sure the text we found is the node the parser found
+    let matches = match parsed {
+        serde_yaml::Value::String(s) => *s == span.value,
+        serde_yaml::Value::Number(_) | serde_yaml::Value::Bool(_) => {
+            span.style == ScalarStyle::Plain
+        }
+        _ => false,
+    };
+    if !matches {
+        return Err(anyhow!("Cannot edit {} in place", describe_path(path)));
+    }
+
+    Ok(Some(span))
+}
+
+/// Replace the scalar at `path` with `new_value`, preserving its quoting style
+/// and everything else in the document, including comments
+pub fn replace_scalar(content: &str, path: &[Segment], new_value: &str) -> Result<String> {
+    let span =
+        find_scalar(content, path)?.ok_or_else(|| anyhow!("No {} found", describe_path(path)))?;
+
+    let escaped = match span.style {
+        ScalarStyle::Plain => new_value.to_string(),
+        ScalarStyle::SingleQuoted => new_value.replace('\'', "''"),
+        ScalarStyle::DoubleQuoted => new_value.replace('\\', "\\\\").replace('"', "\\\""),
+    };
+
+    let mut new_content = content.to_string();
+    new_content.replace_range(span.range, &escaped);
+    Ok(new_content)
+}
+
+/// Format a path for messages, e.g. `dependencies[0].version`
+pub fn describe_path(path: &[Segment]) -> String {
+    let mut description = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                if !description.is_empty() {
+                    description.push('.');
+                }
+                description.push_str(key);
+            }
+            Segment::Index(index) => description.push_str(&format!("[{index}]")),
+        }
+    }
+    description
+}
+
+fn get_node<'a>(value: &'a serde_yaml::Value, path: &[Segment]) -> Option<&'a serde_yaml::Value> {
+    path.iter().try_fold(value, |node, segment| match segment {
+        Segment::Key(key) => node.as_mapping()?.get(*key),
+        Segment::Index(index) => node.as_sequence()?.get(*index),
+    })
+}
+
+// Split the first document into significant lines
+fn significant_lines(content: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    let mut started = false;
+
+    for raw in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += raw.len();
+
+        let text = raw.trim_end_matches(['\n', '\r']);
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('%') {
+            continue;
+        }
+        if text.starts_with("---") || text.starts_with("...") {
+            if started {
+                break;
+            }
+            continue;
+        }
+
+        started = true;
+        let indent = text.len() - trimmed.len();
+        lines.push(Line {
+            indent,
+            start: line_start + indent,
+            text: trimmed,
+        });
+    }
+
+    lines
+}
+
+// Locate the scalar at `path` within a block of lines at the same indentation
+fn find_in_block(lines: &[Line], path: &[Segment]) -> Option<ScalarSpan> {
+    let (segment, rest) = path.split_first()?;
+    let indent = lines.first()?.indent;
+
+    match segment {
+        Segment::Key(key) => {
+            for (i, line) in lines.iter().enumerate() {
+                if line.indent != indent {
+                    continue;
+                }
+                let Some(value_offset) = match_key(line.text, key) else {
+                    continue;
+                };
+
+                let value = Line {
+                    indent: line.indent + value_offset,
+                    start: line.start + value_offset,
+                    text: &line.text[value_offset..],
+                };
+                if rest.is_empty() {
+                    return parse_scalar(value);
+                }
+
+                // The nested block; a sequence may sit at the same indentation
+                // as its key
+                let children: Vec<Line> = lines[i + 1..]
+                    .iter()
+                    .take_while(|l| {
+                        l.indent > indent || (l.indent == indent && is_sequence_item(l.text))
+                    })
+                    .copied()
+                    .collect();
+                if !value.text.trim().is_empty() && !value.text.trim_start().starts_with('#') {
+                    return None;
+                }
+                return find_in_block(&children, rest);
+            }
+            None
+        }
+        Segment::Index(index) => {
+            let (i, line) = lines
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.indent == indent && is_sequence_item(l.text))
+                .nth(*index)?;
+
+            // Treat the text after `- ` as the first line of the item's block
+            let after_dash = &line.text[1..];
+            let gap = after_dash.len() - after_dash.trim_start().len();
+            let mut item: Vec<Line> = Vec::new();
+            if !after_dash.trim().is_empty() {
+                item.push(Line {
+                    indent: line.indent + 1 + gap,
+                    start: line.start + 1 + gap,
+                    text: &after_dash[gap..],
+                });
+            }
+            item.extend(lines[i + 1..].iter().take_while(|l| l.indent > indent));
+
+            if rest.is_empty() {
+                return item.first().and_then(|first| parse_scalar(*first));
+            }
+            find_in_block(&item, rest)
+        }
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+// If `text` starts with the mapping key `key`, return the offset of its value
+fn match_key(text: &str, key: &str) -> Option<usize> {
+    let after_key = [format!("\"{key}\""), format!("'{key}'"), key.to_string()]
+        .iter()
+        .find_map(|k| text.strip_prefix(k.as_str()))?;
+
+    let after_colon = after_key.trim_start().strip_prefix(':')?;
+    if !after_colon.is_empty() && !after_colon.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    let gap = after_colon.len() - after_colon.trim_start().len();
+    Some(text.len() - after_colon.len() + gap)
+}
+
+// Parse the scalar at the start of a line's text
+fn parse_scalar(line: Line) -> Option<ScalarSpan> {
+    let text = line.text;
+
+    if let Some(body) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = body.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    return Some(ScalarSpan {
+                        range: line.start + 1..line.start + 1 + i,
+                        style: ScalarStyle::DoubleQuoted,
+                        value,
+                    })
+                }
+                '\\' => value.push(chars.next()?.1),
+                _ => value.push(c),
+            }
+        }
+        return None;
+    }
+
+    if let Some(body) = text.strip_prefix('\'') {
+        let mut value = String::new();
+        let mut chars = body.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '\'' {
+                if chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                    chars.next();
+                    value.push('\'');
+                    continue;
+                }
+                return Some(ScalarSpan {
+                    range: line.start + 1..line.start + 1 + i,
+                    style: ScalarStyle::SingleQuoted,
+                    value,
+                });
+            }
+            value.push(c);
+        }
+        return None;
+    }
+
+    // Block scalars, flow collections, anchors, aliases and tags
+    if text.is_empty() || text.starts_with(['|', '>', '[', '{', '&', '*', '!', '#']) {
+        return None;
+    }
+
+    // A plain scalar runs to the end of the line or the start of a comment
+    let end = text.find(" #").unwrap_or(text.len());
+    let value = text[..end].trim_end();
+    Some(ScalarSpan {
+        range: line.start..line.start + value.len(),
+        style: ScalarStyle::Plain,
+        value: value.to_string(),
+    })
+}
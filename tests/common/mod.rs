@@ -0,0 +1,14 @@
+use anyhow::Result;
+use std::path::Path;
+use std::process::Command;
+
+// Run git in `dir` as a test user, returning its output
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    assert!(output.status.success(), "git {args:?} failed");
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use project_version::check::check_versions;
+use project_version::project::detect_project;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_check_reports_drifting_versions() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("package.json"),
+        r#"{"name": "app", "version": "1.4.0"}"#,
+    )?;
+    fs::write(
+        temp_dir.path().join("package-lock.json"),
+        r#"{"name": "app", "version": "1.4.0", "packages": {"": {"version": "1.3.2"}}}"#,
+    )?;
+    fs::write(
+        temp_dir.path().join("CHANGELOG.md"),
+        "# Changelog\n\n## [Unreleased]\n\n## [1.4.0] - 2024-05-01\n\n## [1.3.2] - 2024-04-01\n",
+    )?;
+
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let report = check_versions(project.as_ref(), temp_dir.path())?;
+
+    // The manifest, both lock file entries and the changelog are compared
+    let sources: Vec<_> = report.entries.iter().map(|entry| entry.source).collect();
+    assert_eq!(sources, ["manifest", "lock file", "lock file", "changelog"]);
+    assert!(!report.is_consistent());
+
+    // Only the stale lock file entry is flagged
+    let table = report.to_table();
+    assert_eq!(table.matches("MISMATCH").count(), 1);
+    assert!(table.contains("packages[\"\"].version"));
+    assert!(table
+        .lines()
+        .any(|line| line.contains("1.3.2") && line.ends_with("MISMATCH")));
+
+    Ok(())
+}
+
+#[test]
+fn test_check_ignores_independent_app_version() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("Chart.yaml"),
+        "apiVersion: v2\nname: app\nversion: \"1.2.3\"\nappVersion: '1.0'\n",
+    )?;
+
+    // appVersion tracks the application, not the chart
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let report = check_versions(project.as_ref(), temp_dir.path())?;
+    assert!(report.is_consistent());
+    assert!(!report.to_table().contains("appVersion"));
+
+    // Unless it's configured to follow the chart version
+    fs::write(
+        temp_dir.path().join(".project-version.toml"),
+        "[helm]\napp_version = true\n",
+    )?;
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let report = check_versions(project.as_ref(), temp_dir.path())?;
+    assert!(!report.is_consistent());
+    assert!(report
+        .to_table()
+        .lines()
+        .any(|line| line.contains("appVersion") && line.ends_with("MISMATCH")));
+
+    Ok(())
+}
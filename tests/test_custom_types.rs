@@ -0,0 +1,77 @@
+use anyhow::Result;
+use project_version::check::check_versions;
+use project_version::config::ProjectTypeConfig;
+use project_version::custom::CustomDetector;
+use project_version::project::detect_project;
+use semver::Version;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_custom_project_types_from_config() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+    fs::write(
+        dir.join("plugin.xml"),
+        "<plugin>\n  <version>v2.1.0</version>\n  <depends><version>9.9.9</version></depends>\n</plugin>\n",
+    )?;
+    fs::write(
+        dir.join("meta.toml"),
+        "[plugin]\nversion = \"2.1.0\" # released\n",
+    )?;
+    fs::write(
+        dir.join(".project-version.toml"),
+        r#"
+[[types]]
+name = "intellij-plugin"
+detect = "*.xml"
+pattern = '<plugin>\s*<version>(?<version>[^<]+)</version>'
+
+[[types]]
+name = "meta"
+detect = "meta.toml"
+key = "plugin.version"
+priority = -1
+"#,
+    )?;
+
+    // A configured type is detected before the built-in types
+    fs::write(dir.join("VERSION"), "2.1.0\n")?;
+    let project = detect_project(dir.to_str().unwrap())?;
+    assert_eq!(project.get_project_type(), "intellij-plugin");
+    assert_eq!(project.get_version()?, Version::new(2, 1, 0));
+
+    // Only the matched version is replaced, keeping its `v` prefix
+    let changes = project.dry_run_update(&Version::new(2, 2, 0))?;
+    assert_eq!(
+        changes[0].updated,
+        "<plugin>\n  <version>v2.2.0</version>\n  <depends><version>9.9.9</version></depends>\n</plugin>\n"
+    );
+    let report = check_versions(project.as_ref(), dir)?;
+    assert!(report.is_consistent());
+
+    // A key path edits TOML in place
+    fs::remove_file(dir.join("plugin.xml"))?;
+    fs::remove_file(dir.join("VERSION"))?;
+    let project = detect_project(dir.to_str().unwrap())?;
+    assert_eq!(project.get_project_type(), "meta");
+    project.update_version(&Version::new(3, 0, 0))?;
+    assert_eq!(
+        fs::read_to_string(dir.join("meta.toml"))?,
+        "[plugin]\nversion = \"3.0.0\" # released\n"
+    );
+
+    // A type needs a way to find the version
+    let config = ProjectTypeConfig {
+        name: "broken".to_string(),
+        detect: "manifest.ini".to_string(),
+        file: None,
+        pattern: None,
+        key: Some("version".to_string()),
+        format: None,
+        priority: 0,
+    };
+    assert!(CustomDetector::new(&config).is_err());
+
+    Ok(())
+}
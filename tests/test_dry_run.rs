@@ -0,0 +1,89 @@
+use anyhow::Result;
+use project_version::changelog::dry_run_update_changelog;
+use project_version::project::detect_project;
+use semver::Version;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn test_dry_run_shows_unified_diff() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let package_json = r#"{
+  "name": "app",
+  "version": "1.2.3",
+  "engines": {
+    "version": "1.2.3"
+  }
+}
+"#;
+    fs::write(temp_dir.path().join("package.json"), package_json)?;
+    let changelog_path = temp_dir.path().join("CHANGELOG.md");
+    fs::write(
+        &changelog_path,
+        "# Changelog\n\n## [Unreleased]\n\n- Fix\n\n## [1.2.3] - 2024-01-01\n",
+    )?;
+
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let new_version = Version::new(1, 2, 4);
+
+    // Only the root version line is replaced
+    let changes = project.dry_run_update(&new_version)?;
+    assert_eq!(changes.len(), 1);
+    let diff = changes[0].unified_diff();
+    assert!(diff.contains("-  \"version\": \"1.2.3\",\n+  \"version\": \"1.2.4\",\n"));
+    assert_eq!(diff.matches("\n- ").count(), 1);
+    assert_eq!(diff.matches("\n+ ").count(), 1);
+
+    // The changelog diff shows the new release heading
+    let change = dry_run_update_changelog(&changelog_path, &new_version)?.unwrap();
+    assert!(change
+        .unified_diff()
+        .contains("-## [Unreleased]\n+## [1.2.4] - "));
+
+    // Nothing is written
+    let content = fs::read_to_string(temp_dir.path().join("package.json"))?;
+    assert_eq!(content, package_json);
+    assert!(fs::read_to_string(&changelog_path)?.contains("## [Unreleased]"));
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_shows_diff_of_configured_files() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("package.json"),
+        r#"{"name": "app", "version": "1.2.3"}"#,
+    )?;
+    let readme = "# App\n\nInstall app@1.2.3 to get started.\n";
+    fs::write(temp_dir.path().join("README.md"), readme)?;
+    fs::write(
+        temp_dir.path().join(".project-version.toml"),
+        "[[files]]\npath = \"README.md\"\nsearch = \"app@{current_version}\"\nreplace = \"app@{new_version}\"\n",
+    )?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_project-version"))
+        .args(["--dry-run", "bump", "--no-lockupdate"])
+        .current_dir(temp_dir.path())
+        .env("NO_COLOR", "1")
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+
+    // Configured files are shown as a diff like the project file
+    assert!(stdout.contains("+++ README.md"));
+    assert!(
+        stdout.contains("-Install app@1.2.3 to get started.\n+Install app@1.2.4 to get started.\n")
+    );
+    assert!(!stdout.contains("replacement"));
+
+    // Planning outside a git repository doesn't warn about the tag
+    assert!(!String::from_utf8(output.stderr)?.contains("Not a git repository"));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("README.md"))?,
+        readme
+    );
+
+    Ok(())
+}
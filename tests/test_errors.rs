@@ -0,0 +1,58 @@
+use anyhow::Result;
+use project_version::error::{exit_code, Error};
+use project_version::project::detect_project;
+use project_version::release::Releaser;
+use semver::Version;
+use std::fs;
+use std::process::ExitCode;
+use tempfile::tempdir;
+
+#[test]
+fn test_typed_errors() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+
+    // An empty directory has no project
+    let error = detect_project(dir.to_str().unwrap()).err().unwrap();
+    assert!(matches!(&error, Error::NoProject { dir: d } if d == dir));
+    assert_eq!(error.exit_code(), 3);
+
+    // An unparsable version names the file and the value
+    let package_json_path = dir.join("package.json");
+    fs::write(&package_json_path, r#"{"name": "app", "version": "1.x"}"#)?;
+    let project = detect_project(dir.to_str().unwrap())?;
+    match project.get_version().unwrap_err() {
+        Error::InvalidVersion { path, value, .. } => {
+            assert_eq!(path, package_json_path);
+            assert_eq!(value, "1.x");
+        }
+        error => panic!("unexpected error: {error}"),
+    }
+
+    // A project file that can't be read names the file
+    fs::remove_file(&package_json_path)?;
+    let error = project.get_version().unwrap_err();
+    assert!(matches!(&error, Error::Io { path, .. } if *path == package_json_path));
+    assert_eq!(error.exit_code(), 8);
+
+    // A release reports why it can't go ahead
+    fs::write(&package_json_path, r#"{"name": "app", "version": "1.2.3"}"#)?;
+    let error = Releaser::new(dir)
+        .set(Version::new(1, 0, 0))
+        .run()
+        .unwrap_err();
+    assert!(matches!(
+        error.root(),
+        Error::VersionNotIncreasing { current, new }
+            if *current == Version::new(1, 2, 3) && *new == Version::new(1, 0, 0)
+    ));
+
+    // Typed errors survive being passed along as anyhow errors
+    assert_eq!(exit_code(&error.into()), ExitCode::from(5));
+    assert_eq!(
+        exit_code(&anyhow::anyhow!("something else")),
+        ExitCode::from(1)
+    );
+
+    Ok(())
+}
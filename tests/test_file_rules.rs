@@ -0,0 +1,67 @@
+use anyhow::Result;
+use semver::Version;
+use std::fs;
+use tempfile::tempdir;
+
+use project_version::config::load_config;
+use project_version::diff::apply_all;
+use project_version::file_rules::compute_changes;
+
+#[test]
+fn test_configured_file_rules() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+
+    // Configure a literal README rule and a templated Dockerfile rule
+    fs::write(
+        dir.join(".project-version.toml"),
+        r#"[[files]]
+path = "README.md"
+
+[[files]]
+path = "docker/*"
+search = "LABEL version={current_version}"
+replace = "LABEL version={new_version}"
+"#,
+    )?;
+    fs::write(
+        dir.join("README.md"),
+        "Install with `pip install test==1.2.3`.\nRequires other==1.2.3 or later.\n",
+    )?;
+    fs::create_dir_all(dir.join("docker"))?;
+    fs::write(
+        dir.join("docker/Dockerfile"),
+        "FROM python:3.12\nLABEL version=1.2.3\nRUN pip install other==1.2.3\n",
+    )?;
+
+    let config = load_config(dir.to_str().unwrap())?;
+    assert_eq!(config.files.len(), 2);
+
+    let current_version = Version::new(1, 2, 3);
+    let new_version = Version::new(1, 3, 0);
+
+    // Computing the changes leaves every file untouched
+    let changes = compute_changes(dir, &config.files, &current_version, &new_version, &[])?;
+    assert_eq!(changes.len(), 2);
+    assert!(changes[0].path.ends_with("README.md"));
+    assert!(changes[1].path.ends_with("docker/Dockerfile"));
+    assert!(changes[0]
+        .unified_diff()
+        .contains("+Requires other==1.3.0 or later."));
+    assert!(fs::read_to_string(dir.join("README.md"))?.contains("1.2.3"));
+
+    apply_all(&changes)?;
+
+    // Only the text matched by each rule was replaced
+    let readme = fs::read_to_string(dir.join("README.md"))?;
+    assert!(!readme.contains("1.2.3"));
+    let dockerfile = fs::read_to_string(dir.join("docker/Dockerfile"))?;
+    assert!(dockerfile.contains("LABEL version=1.3.0"));
+    assert!(dockerfile.contains("other==1.2.3"));
+
+    // A rule that no longer matches fails loudly
+    let result = compute_changes(dir, &config.files, &current_version, &new_version, &[]);
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn test_get_command_output() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("Chart.yaml"),
+        "apiVersion: v2\nname: app\nversion: 1.2.3\nappVersion: \"4.5.6\"\n",
+    )?;
+    fs::write(
+        temp_dir.path().join(".project-version.toml"),
+        "[helm]\napp_version = true\n",
+    )?;
+    fs::write(
+        temp_dir.path().join("Chart.lock"),
+        "dependencies: []\ndigest: sha256:0\n",
+    )?;
+
+    let run = |args: &[&str]| -> Result<String> {
+        let output = Command::new(env!("CARGO_BIN_EXE_project-version"))
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    // Plain output is just the version
+    assert_eq!(run(&["get"])?, "1.2.3\n");
+
+    // JSON output describes the project and every version location
+    let report: serde_json::Value = serde_json::from_str(&run(&["get", "--format", "json"])?)?;
+    assert_eq!(report["type"], "helm");
+    assert_eq!(report["version"], "1.2.3");
+    assert_eq!(report["locations"][1]["field"], "appVersion");
+    assert_eq!(report["locations"][1]["value"], "4.5.6");
+
+    // A JSON release report replaces the progress messages
+    let report: serde_json::Value =
+        serde_json::from_str(&run(&["bump", "minor", "--dry-run", "--output", "json"])?)?;
+    assert_eq!(report["old_version"], "1.2.3");
+    assert_eq!(report["new_version"], "1.3.0");
+    assert_eq!(report["dry_run"], true);
+    assert!(report["commit"].is_null());
+
+    // Only files whose content changes are listed
+    assert_eq!(report["files"], serde_json::json!(["./Chart.yaml"]));
+
+    Ok(())
+}
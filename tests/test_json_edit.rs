@@ -0,0 +1,59 @@
+use anyhow::Result;
+
+use project_version::json_edit::{find_string, replace_string, Segment};
+
+#[test]
+fn test_json_edit_targets_root_member() -> Result<()> {
+    let content = r#"{
+  "name": "test-project",
+  "publishConfig": { "version": "0.0.1" },
+  "overrides": { "foo": { "version": "2.0.0" } },
+  "version": "1.2.3",
+  "engines": { "node": ">=18" }
+}
+"#;
+
+    // Nested "version" keys that come first are ignored
+    let span = find_string(content, &[Segment::Key("version")])?.unwrap();
+    assert_eq!(span.value, "1.2.3");
+
+    let updated = replace_string(content, &[Segment::Key("version")], "1.3.0")?;
+    assert_eq!(
+        updated,
+        content.replace(r#""version": "1.2.3""#, r#""version": "1.3.0""#)
+    );
+
+    // Nested members can be addressed explicitly
+    let path = [
+        Segment::Key("overrides"),
+        Segment::Key("foo"),
+        Segment::Key("version"),
+    ];
+    assert_eq!(find_string(content, &path)?.unwrap().value, "2.0.0");
+
+    Ok(())
+}
+
+#[test]
+fn test_json_edit_accepts_comments_and_json5() -> Result<()> {
+    let content = r#"// pnpm manifest
+{
+  name: 'test-project', /* unquoted keys */
+  // "version": "0.0.0",
+  version: '1.2.3',
+  files: ['dist', 'README.md',],
+}
+"#;
+
+    let updated = replace_string(content, &[Segment::Key("version")], "2.0.0")?;
+    assert!(updated.contains("  version: '2.0.0',\n"));
+    assert!(updated.contains(r#"  // "version": "0.0.0","#));
+
+    let files = [Segment::Key("files"), Segment::Index(1)];
+    assert_eq!(find_string(content, &files)?.unwrap().value, "README.md");
+
+    // Malformed documents are reported rather than edited
+    assert!(find_string("{ \"version\": ", &[Segment::Key("version")]).is_err());
+
+    Ok(())
+}
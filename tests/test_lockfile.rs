@@ -0,0 +1,168 @@
+use anyhow::Result;
+use project_version::diff::apply_all;
+use project_version::lockfile;
+use project_version::project::detect_project;
+use semver::Version;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_offline_lockfile_updates_only_own_entry() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let member_path = temp_dir.path().join("crates/core/Cargo.toml");
+    fs::create_dir_all(member_path.parent().unwrap())?;
+
+    // Create a workspace whose members inherit the workspace version
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        r#"[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "1.2.3"
+"#,
+    )?;
+    fs::write(
+        &member_path,
+        "[package]\nname = \"core\"\nversion.workspace = true\n",
+    )?;
+    let cargo_lock_path = temp_dir.path().join("Cargo.lock");
+    fs::write(
+        &cargo_lock_path,
+        r#"version = 3
+
+[[package]]
+name = "core"
+version = "1.2.3"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "serde"
+version = "1.2.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+    )?;
+
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let new_version = Version::new(1, 3, 0);
+
+    // Computing the changes leaves the lock file alone
+    let changes = project.dry_run_update_lockfiles(&new_version)?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, cargo_lock_path);
+    assert!(fs::read_to_string(&cargo_lock_path)?.contains("name = \"core\"\nversion = \"1.2.3\""));
+
+    // Verify only the workspace member was updated
+    apply_all(&changes)?;
+    let cargo_lock = fs::read_to_string(&cargo_lock_path)?;
+    assert!(cargo_lock.contains("name = \"core\"\nversion = \"1.3.0\""));
+    assert!(cargo_lock.contains("name = \"serde\"\nversion = \"1.2.3\""));
+
+    // package-lock.json: the root version and packages[""], not dependencies
+    let package_lock = r#"{
+  "name": "app",
+  "version": "1.2.3",
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app", "version": "1.2.3" },
+    "node_modules/dep": { "version": "1.2.3" }
+  }
+}"#;
+    let updated = lockfile::update_package_lock(package_lock, &new_version)?.unwrap();
+    assert_eq!(updated.matches("1.3.0").count(), 2);
+    assert!(updated.contains(r#""node_modules/dep": { "version": "1.2.3" }"#));
+
+    // uv.lock: the editable root package, matched by normalized name
+    let uv_lock = r#"version = 1
+
+[[package]]
+name = "my-app"
+version = "1.2.3"
+source = { editable = "." }
+
+[[package]]
+name = "requests"
+version = "1.2.3"
+source = { registry = "https://pypi.org/simple" }
+"#;
+    let updated = lockfile::update_uv_lock(uv_lock, "My_App", &new_version)?.unwrap();
+    assert!(updated.contains("name = \"my-app\"\nversion = \"1.3.0\""));
+    assert!(updated.contains("name = \"requests\"\nversion = \"1.2.3\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_lockfile_hashes_find_changed_lockfiles() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"1.2.3\"\n",
+    )?;
+    let cargo_lock_path = temp_dir.path().join("Cargo.lock");
+    fs::write(&cargo_lock_path, "version = 3\n")?;
+
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    assert_eq!(project.get_lockfiles(), vec![cargo_lock_path.clone()]);
+
+    // Unchanged lock files aren't reported
+    let hashes = lockfile::LockfileHashes::capture(&project.get_lockfiles());
+    assert!(hashes.changed().is_empty());
+
+    // Changed (or newly created) ones are
+    fs::write(&cargo_lock_path, "version = 4\n")?;
+    assert_eq!(hashes.changed(), vec![cargo_lock_path]);
+
+    Ok(())
+}
+
+#[test]
+fn test_offline_update_leaves_pnpm_lock_to_pnpm() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("package.json"),
+        r#"{"name": "app", "version": "1.2.3"}"#,
+    )?;
+
+    // pnpm v9 links workspace packages instead of recording their versions
+    let pnpm_lock = r#"lockfileVersion: '9.0'
+
+settings:
+  autoInstallPeers: true
+  excludeLinksFromLockfile: false
+
+importers:
+
+  .:
+    dependencies:
+      lodash:
+        specifier: ^4.17.21
+        version: 4.17.21
+
+  packages/web:
+    dependencies:
+      app:
+        specifier: workspace:*
+        version: link:../..
+
+packages:
+
+  lodash@4.17.21:
+    resolution: {integrity: sha512-v2kDEe57lecTulaDIuNTPy3Ry4gLGJ6Z1O3vE1krgXZNrsQ+LFTGHVxVjcXPs17LhbZVGedAJv8XZ1tvj5FvSg==}
+
+snapshots:
+
+  lodash@4.17.21: {}
+"#;
+    let pnpm_lock_path = temp_dir.path().join("pnpm-lock.yaml");
+    fs::write(&pnpm_lock_path, pnpm_lock)?;
+
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let changes = project.dry_run_update_lockfiles(&Version::new(1, 3, 0))?;
+    assert!(changes.is_empty());
+    assert_eq!(fs::read_to_string(&pnpm_lock_path)?, pnpm_lock);
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use anyhow::Result;
+use common::git;
+use project_version::bump::{next_version, BumpType};
+use project_version::project::detect_project;
+use semver::Version;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_next_version_computation() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(temp_dir.path().join("VERSION"), "1.2.3\n")?;
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let next = |current: &str, bump_type| -> Result<String> {
+        let current = Version::parse(current)?;
+        Ok(next_version(project.as_ref(), temp_dir.path(), &current, bump_type)?.to_string())
+    };
+
+    assert_eq!(next("1.2.3", BumpType::Major)?, "2.0.0");
+    assert_eq!(next("1.2.3", BumpType::Minor)?, "1.3.0");
+    assert_eq!(next("1.2.3", BumpType::Patch)?, "1.2.4");
+
+    // Prereleases are numbered npm-style
+    assert_eq!(next("1.2.3", BumpType::Prerelease)?, "1.2.4-0");
+    assert_eq!(next("1.2.4-rc.1", BumpType::Prerelease)?, "1.2.4-rc.2");
+    assert_eq!(next("1.2.4-beta", BumpType::Prerelease)?, "1.2.4-beta.0");
+
+    // Auto follows the conventional commits since the release tag
+    let dir = temp_dir.path();
+    git(dir, &["init", "-q"])?;
+    git(
+        dir,
+        &[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "feat: initial release",
+        ],
+    )?;
+    git(dir, &["tag", "v1.2.3"])?;
+    assert!(next("1.2.3", BumpType::Auto).is_err());
+
+    git(
+        dir,
+        &[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "fix(parser): handle tabs",
+        ],
+    )?;
+    assert_eq!(next("1.2.3", BumpType::Auto)?, "1.2.4");
+    git(
+        dir,
+        &["commit", "-q", "--allow-empty", "-m", "feat: add option"],
+    )?;
+    assert_eq!(next("1.2.3", BumpType::Auto)?, "1.3.0");
+    git(
+        dir,
+        &[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "refactor: x\n\nBREAKING CHANGE: removed y",
+        ],
+    )?;
+    assert_eq!(next("1.2.3", BumpType::Auto)?, "2.0.0");
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use project_version::project::{detect_project, PackageManagerCommand};
+use project_version::snapshot::Snapshot;
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_package_manager_command_and_rollback() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let package_json_path = temp_dir.path().join("package.json");
+    let package_lock_path = temp_dir.path().join("package-lock.json");
+    fs::write(&package_json_path, r#"{"name": "app", "version": "1.2.3"}"#)?;
+    fs::write(&package_lock_path, r#"{"name": "app", "version": "1.2.3"}"#)?;
+
+    // npm only refreshes the lock file, which is reported for committing
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    let command = project.get_package_manager_update_command().unwrap();
+    assert_eq!(command.to_string(), "npm install --package-lock-only");
+    assert!(command.needs_network);
+    assert_eq!(command.lockfile, Some(package_lock_path.clone()));
+
+    // Other package managers refresh the lock file without upgrading the
+    // locked dependencies where they can
+    let cases = [
+        ("pyproject.toml", "poetry.lock", "# This file is automatically @generated by Poetry 1.8.3 and should not be changed by hand.\n", "poetry lock --no-update"),
+        ("pyproject.toml", "poetry.lock", "# This file is automatically @generated by Poetry 2.1.2 and should not be changed by hand.\n", "poetry lock"),
+        ("package.json", "yarn.lock", "# This file is generated by running \"yarn install\" inside your project.\n\n__metadata:\n  version: 8\n", "yarn install --mode=update-lockfile"),
+        ("package.json", "yarn.lock", "# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.\n# yarn lockfile v1\n", "yarn install"),
+        ("package.json", "bun.lockb", "", "bun install --lockfile-only"),
+    ];
+    for (manifest, lockfile, lock_content, expected) in cases {
+        let dir = tempdir()?;
+        let manifest_content = match manifest {
+            "package.json" => r#"{"name": "app", "version": "1.2.3"}"#,
+            _ => "[project]\nname = \"app\"\nversion = \"1.2.3\"\n",
+        };
+        fs::write(dir.path().join(manifest), manifest_content)?;
+        fs::write(dir.path().join(lockfile), lock_content)?;
+        let project = detect_project(dir.path().to_str().unwrap())?;
+        let command = project.get_package_manager_update_command().unwrap();
+        assert_eq!(command.to_string(), expected);
+    }
+
+    // Output is returned; failures and timeouts are errors
+    let echo = PackageManagerCommand::new("sh", &["-c", "echo locked"]);
+    assert_eq!(
+        echo.run(temp_dir.path(), Duration::from_secs(10))?,
+        "locked\n"
+    );
+    let failing = PackageManagerCommand::new("sh", &["-c", "echo oops >&2; exit 1"]);
+    let error = failing
+        .run(temp_dir.path(), Duration::from_secs(10))
+        .unwrap_err();
+    assert!(error.to_string().contains("oops"));
+    let slow = PackageManagerCommand::new("sleep", &["10"]);
+    let error = slow
+        .run(temp_dir.path(), Duration::from_millis(100))
+        .unwrap_err();
+    assert!(error.to_string().contains("timed out"));
+
+    // A snapshot restores changed files and removes created ones
+    let cargo_lock_path = temp_dir.path().join("Cargo.lock");
+    let snapshot = Snapshot::capture(vec![package_json_path.clone(), cargo_lock_path.clone()])?;
+    project.update_version(&semver::Version::new(1, 3, 0))?;
+    fs::write(&cargo_lock_path, "")?;
+    snapshot.restore()?;
+    assert!(fs::read_to_string(&package_json_path)?.contains("1.2.3"));
+    assert!(!cargo_lock_path.exists());
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
+mod common;
+
 use anyhow::Result;
+use common::git;
 use semver::Version;
 use std::fs;
 use tempfile::tempdir;
@@ -207,3 +210,522 @@ fn test_changelog_update() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_mix_project_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let mix_path = temp_dir.path().join("mix.exs");
+
+    // Create a mix.exs that takes its version from a module attribute
+    fs::write(
+        &mix_path,
+        r#"defmodule MyApp.MixProject do
+  use Mix.Project
+
+  @version "1.2.3"
+
+  def project do
+    [
+      app: :my_app,
+      version: @version,
+      elixir: "~> 1.15",
+      deps: deps()
+    ]
+  end
+
+  defp deps do
+    [
+      {:jason, "~> 1.4", version: "1.4.0"}
+    ]
+  end
+end
+"#,
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify detected version
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(1, 2, 3));
+
+    // Test update
+    let new_version = Version::new(1, 3, 0);
+    project.update_version(&new_version)?;
+
+    // Verify the attribute was updated and the rest of the file left alone
+    let updated_content = fs::read_to_string(&mix_path)?;
+    assert!(updated_content.contains(r#"@version "1.3.0""#));
+    assert!(updated_content.contains("version: @version,"));
+    assert!(updated_content.contains(r#"version: "1.4.0"}"#));
+
+    Ok(())
+}
+
+#[test]
+fn test_xcode_project_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let xcodeproj_dir = temp_dir.path().join("MyApp.xcodeproj");
+    fs::create_dir_all(&xcodeproj_dir)?;
+    fs::create_dir_all(temp_dir.path().join("MyApp"))?;
+    let pbxproj_path = xcodeproj_dir.join("project.pbxproj");
+    let info_plist_path = temp_dir.path().join("MyApp/Info.plist");
+
+    // Create a project.pbxproj with Debug and Release build configurations
+    fs::write(
+        &pbxproj_path,
+        r#"// !$*UTF8*$!
+{
+	objects = {
+		1A2B3C /* Debug */ = {
+			buildSettings = {
+				CURRENT_PROJECT_VERSION = 41;
+				INFOPLIST_FILE = MyApp/Info.plist;
+				MARKETING_VERSION = 1.2;
+			};
+			name = Debug;
+		};
+		4D5E6F /* Release */ = {
+			buildSettings = {
+				CURRENT_PROJECT_VERSION = 41;
+				INFOPLIST_FILE = MyApp/Info.plist;
+				MARKETING_VERSION = 1.2;
+			};
+			name = Release;
+		};
+	};
+}
+"#,
+    )?;
+
+    // Create an Info.plist with literal version values
+    fs::write(
+        &info_plist_path,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>CFBundleShortVersionString</key>
+	<string>1.2</string>
+	<key>CFBundleVersion</key>
+	<string>41</string>
+</dict>
+</plist>
+"#,
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify detected version
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(1, 2, 0));
+
+    // Test update
+    let new_version = Version::new(1, 3, 0);
+    project.update_version(&new_version)?;
+
+    // Verify every configuration was updated and the build number incremented
+    let updated_content = fs::read_to_string(&pbxproj_path)?;
+    assert_eq!(
+        updated_content
+            .matches("MARKETING_VERSION = 1.3.0;")
+            .count(),
+        2
+    );
+    assert_eq!(
+        updated_content
+            .matches("CURRENT_PROJECT_VERSION = 42;")
+            .count(),
+        2
+    );
+
+    let updated_plist = fs::read_to_string(&info_plist_path)?;
+    assert!(updated_plist.contains("<string>1.3.0</string>"));
+    assert!(updated_plist.contains("<string>42</string>"));
+    assert!(project.get_files_to_commit().contains(&info_plist_path));
+
+    Ok(())
+}
+
+#[test]
+fn test_python_dynamic_version_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let pyproject_path = temp_dir.path().join("pyproject.toml");
+    let init_path = temp_dir.path().join("src/test_project/__init__.py");
+    fs::create_dir_all(init_path.parent().unwrap())?;
+
+    // Create a pyproject.toml whose version is read from the package
+    fs::write(
+        &pyproject_path,
+        r#"[project]
+name = "test-project"
+dynamic = ["version"]
+
+[tool.setuptools.dynamic]
+version = {attr = "test_project.__version__"}
+"#,
+    )?;
+    fs::write(
+        &init_path,
+        "\"\"\"Test project.\"\"\"\n\n__version__ = '0.5.1'\n",
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify detected version
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(0, 5, 1));
+
+    // Test update
+    let new_version = Version::new(0, 6, 0);
+    project.update_version(&new_version)?;
+
+    // Verify the module was updated, keeping its quote style
+    let updated_content = fs::read_to_string(&init_path)?;
+    assert!(updated_content.contains("__version__ = '0.6.0'"));
+    assert!(project.get_files_to_commit().contains(&init_path));
+
+    // Verify the version is reported where it is defined
+    assert_eq!(project.get_project_type(), "python");
+    let locations = project.get_version_locations()?;
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].path, init_path);
+    assert_eq!(locations[0].field, "__version__");
+    assert_eq!(locations[0].value, "0.6.0");
+
+    Ok(())
+}
+
+#[test]
+fn test_setup_cfg_project_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let setup_cfg_path = temp_dir.path().join("setup.cfg");
+    let init_path = temp_dir.path().join("test_project/__init__.py");
+    fs::create_dir_all(init_path.parent().unwrap())?;
+
+    // Create a setup.cfg that reads its version from the package
+    fs::write(
+        &setup_cfg_path,
+        "[metadata]\nname = test-project\nversion = attr: test_project.__version__\n\n[options]\npackages = find:\n",
+    )?;
+    fs::write(&init_path, "__version__ = \"1.0.2\"\n")?;
+    fs::write(
+        temp_dir.path().join("setup.py"),
+        "from setuptools import setup\n\nsetup()\n",
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify detected version
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(1, 0, 2));
+
+    // Test update
+    let new_version = Version::new(1, 1, 0);
+    project.update_version(&new_version)?;
+
+    // Verify the module was updated and setup.cfg left alone
+    let updated_content = fs::read_to_string(&init_path)?;
+    assert_eq!(updated_content, "__version__ = \"1.1.0\"\n");
+    let setup_cfg = fs::read_to_string(&setup_cfg_path)?;
+    assert!(setup_cfg.contains("version = attr: test_project.__version__"));
+
+    Ok(())
+}
+
+#[test]
+fn test_setup_py_project_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let setup_py_path = temp_dir.path().join("setup.py");
+
+    // Create a setup.py with a literal version
+    fs::write(
+        &setup_py_path,
+        r#"from setuptools import setup
+
+setup(
+    name="test-project",
+    version='2.3.4',
+    install_requires=["requests>=2.0"],
+)
+"#,
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify detected version
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(2, 3, 4));
+
+    // Test update
+    let new_version = Version::new(2, 3, 5);
+    project.update_version(&new_version)?;
+
+    // Verify updated version
+    let updated_content = fs::read_to_string(&setup_py_path)?;
+    assert!(updated_content.contains("version='2.3.5',"));
+
+    Ok(())
+}
+
+#[test]
+fn test_go_major_version_migration() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let go_mod_path = temp_dir.path().join("go.mod");
+    let main_go_path = temp_dir.path().join("cmd/foo/main.go");
+    fs::create_dir_all(main_go_path.parent().unwrap())?;
+
+    // Create a v1 module whose command imports one of its own packages
+    fs::write(&go_mod_path, "module example.com/foo\n\ngo 1.21\n")?;
+    fs::write(
+        temp_dir.path().join("version.go"),
+        "package foo\n\nconst Version = \"v1.4.0\"\n",
+    )?;
+    fs::write(
+        &main_go_path,
+        r#"package main
+
+import (
+	"fmt"
+
+	"example.com/foo/internal/util"
+	"example.com/foobar"
+)
+
+func main() { fmt.Println(util.Name, foobar.Name) }
+"#,
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    assert_eq!(project.get_version()?, Version::new(1, 4, 0));
+
+    // A dry run shows the edits to every file that would be migrated
+    let new_version = Version::new(2, 0, 0);
+    let changes = project.dry_run_update(&new_version)?;
+    let go_mod_diff = changes
+        .iter()
+        .find(|change| change.path == go_mod_path)
+        .map(|change| change.unified_diff())
+        .unwrap();
+    assert!(go_mod_diff.contains("-module example.com/foo\n"));
+    assert!(go_mod_diff.contains("+module example.com/foo/v2\n"));
+    assert!(changes.iter().any(|change| change.path == main_go_path));
+    assert!(fs::read_to_string(&go_mod_path)?.starts_with("module example.com/foo\n"));
+
+    // Test update
+    project.update_version(&new_version)?;
+
+    // Verify the module path and self-imports moved to /v2
+    let go_mod = fs::read_to_string(&go_mod_path)?;
+    assert!(go_mod.starts_with("module example.com/foo/v2\n"));
+    let main_go = fs::read_to_string(&main_go_path)?;
+    assert!(main_go.contains(r#""example.com/foo/v2/internal/util""#));
+    assert!(main_go.contains(r#""example.com/foobar""#));
+    assert_eq!(project.get_tag_name(&new_version), "v2.0.0");
+
+    Ok(())
+}
+
+#[test]
+fn test_go_version_from_git_tags() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let go_mod_path = temp_dir.path().join("go.mod");
+    fs::write(&go_mod_path, "module example.com/foo\n\ngo 1.21\n")?;
+
+    // Create a repository with release tags but no version.go
+    git(temp_dir.path(), &["init", "-q"])?;
+    git(temp_dir.path(), &["add", "go.mod"])?;
+    git(temp_dir.path(), &["commit", "-q", "-m", "initial"])?;
+    git(temp_dir.path(), &["tag", "v1.2.0"])?;
+    git(temp_dir.path(), &["tag", "v1.10.0"])?;
+    git(temp_dir.path(), &["tag", "tools/v3.0.0"])?;
+    git(temp_dir.path(), &["tag", "not-a-version"])?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify the highest semver tag is used
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(1, 10, 0));
+
+    // With nothing to edit, the tag is the only artifact
+    project.update_version(&Version::new(1, 10, 1))?;
+    let go_mod = fs::read_to_string(&go_mod_path)?;
+    assert_eq!(go_mod, "module example.com/foo\n\ngo 1.21\n");
+
+    // An invalid version fails the update instead of being assumed
+    fs::write(
+        temp_dir.path().join("version.go"),
+        "package foo\n\nconst Version = \"v1.02.0\"\n",
+    )?;
+    assert!(project.dry_run_update(&Version::new(1, 10, 1)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_git_tag_project_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    fs::write(temp_dir.path().join("README.md"), "# Docs\n")?;
+
+    // Create a repository with no manifest, only release tags
+    git(temp_dir.path(), &["init", "-q"])?;
+    git(temp_dir.path(), &["add", "README.md"])?;
+    git(temp_dir.path(), &["commit", "-q", "-m", "initial"])?;
+    git(temp_dir.path(), &["tag", "v0.3.0"])?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify the version comes from the tag and nothing needs editing
+    assert_eq!(project.get_version()?, Version::new(0, 3, 0));
+    project.update_version(&Version::new(0, 4, 0))?;
+    assert!(project.get_files_to_commit().is_empty());
+    assert_eq!(project.get_tag_name(&Version::new(0, 4, 0)), "v0.4.0");
+
+    Ok(())
+}
+
+#[test]
+fn test_version_file_project_detection() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let version_path = temp_dir.path().join("VERSION");
+
+    // Create a single-line VERSION file
+    fs::write(&version_path, "3.1.4\n")?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+
+    // Verify detected version
+    let version = project.get_version()?;
+    assert_eq!(version, Version::new(3, 1, 4));
+
+    // Test update
+    let new_version = Version::new(3, 2, 0);
+    project.update_version(&new_version)?;
+
+    // Verify the trailing newline was preserved
+    let updated_content = fs::read_to_string(&version_path)?;
+    assert_eq!(updated_content, "3.2.0\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_chart_app_version_and_dependencies() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let chart_path = temp_dir.path().join("Chart.yaml");
+    let subchart_path = temp_dir.path().join("charts/backend/Chart.yaml");
+    fs::create_dir_all(subchart_path.parent().unwrap())?;
+
+    // Configure appVersion and subchart synchronization
+    fs::write(
+        temp_dir.path().join(".project-version.toml"),
+        "[helm]\napp_version = true\nupdate_dependencies = true\n",
+    )?;
+
+    // Create an umbrella chart with a local and a remote dependency
+    fs::write(
+        &chart_path,
+        r#"apiVersion: v2
+name: umbrella
+version: 1.2.3
+appVersion: "1.2.3" # the application release
+dependencies:
+  - name: backend
+    version: 1.2.3
+    repository: file://charts/backend
+  - name: redis
+    version: 18.1.0
+    repository: https://charts.bitnami.com/bitnami
+"#,
+    )?;
+    fs::write(
+        &subchart_path,
+        "apiVersion: v2\nname: backend\nversion: 1.2.3\n",
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    assert_eq!(project.get_version()?, Version::new(1, 2, 3));
+
+    // Test update
+    let new_version = Version::new(1, 3, 0);
+    project.update_version(&new_version)?;
+
+    // Verify appVersion keeps its quotes and comment, and only the local
+    // dependency was updated
+    let updated_content = fs::read_to_string(&chart_path)?;
+    assert!(updated_content
+        .contains("version: 1.3.0\nappVersion: \"1.3.0\" # the application release\n"));
+    assert!(updated_content.contains("  - name: backend\n    version: 1.3.0\n"));
+    assert!(updated_content.contains("  - name: redis\n    version: 18.1.0\n"));
+
+    // The subchart keeps its own version
+    assert_eq!(
+        fs::read_to_string(&subchart_path)?,
+        "apiVersion: v2\nname: backend\nversion: 1.2.3\n"
+    );
+    assert!(!project.get_files_to_commit().contains(&subchart_path));
+
+    Ok(())
+}
+
+#[test]
+fn test_ruby_project_follows_version_constant() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let version_rb_path = temp_dir.path().join("lib/my_gem/core_ext/version.rb");
+    fs::create_dir_all(version_rb_path.parent().unwrap())?;
+
+    // Create a gem whose gemspec reads a namespaced VERSION constant
+    fs::write(
+        temp_dir.path().join("Gemfile"),
+        "source \"https://rubygems.org\"\ngemspec\n",
+    )?;
+    fs::write(
+        temp_dir.path().join("my_gem.gemspec"),
+        r#"require_relative "lib/my_gem/core_ext/version"
+
+Gem::Specification.new do |spec|
+  spec.name = "my_gem"
+  spec.version = MyGem::CoreExt::VERSION
+  spec.add_dependency "rake", "~> 13.0.0"
+end
+"#,
+    )?;
+    fs::write(
+        &version_rb_path,
+        "module MyGem\n  module CoreExt\n    VERSION = \"1.2.3\".freeze\n  end\nend\n",
+    )?;
+    fs::write(
+        temp_dir.path().join("Gemfile.lock"),
+        "PATH\n  remote: .\n  specs:\n    my_gem (1.2.3)\n      rake (~> 13.0.0)\n\nGEM\n  remote: https://rubygems.org/\n  specs:\n    rake (13.0.6)\n",
+    )?;
+
+    // Detect project type
+    let project = detect_project(temp_dir.path().to_str().unwrap())?;
+    assert_eq!(project.get_version()?, Version::new(1, 2, 3));
+
+    // Test update
+    let new_version = Version::new(1, 3, 0);
+    project.update_version(&new_version)?;
+
+    // Verify the constant keeps its quotes and .freeze, and the dependency
+    // version in the gemspec is untouched
+    let version_rb = fs::read_to_string(&version_rb_path)?;
+    assert!(version_rb.contains("VERSION = \"1.3.0\".freeze"));
+    let gemspec = fs::read_to_string(temp_dir.path().join("my_gem.gemspec"))?;
+    assert!(gemspec.contains("\"~> 13.0.0\""));
+
+    // Verify only the gem's own Gemfile.lock entry was refreshed
+    let lockfile = fs::read_to_string(temp_dir.path().join("Gemfile.lock"))?;
+    assert!(lockfile.contains("    my_gem (1.3.0)\n"));
+    assert!(lockfile.contains("    rake (13.0.6)\n"));
+
+    Ok(())
+}
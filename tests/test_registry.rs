@@ -0,0 +1,62 @@
+use anyhow::Result;
+use project_version::error::Error;
+use project_version::project::{Project, VersionFileProject};
+use project_version::registry::{FnDetector, ProjectRegistry};
+use project_version::release::Releaser;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_registry_detection_order_and_forced_type() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+    fs::write(
+        dir.join("package.json"),
+        r#"{"name": "app", "version": "1.2.3"}"#,
+    )?;
+    fs::write(dir.join("VERSION"), "2.0.0\n")?;
+    fs::write(dir.join("RELEASE"), "3.0.0\n")?;
+
+    // Built-in types are tried from the highest priority down
+    let mut registry = ProjectRegistry::default();
+    let priorities: Vec<_> = registry.detectors().map(|d| d.priority()).collect();
+    assert!(priorities.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert_eq!(registry.detect(dir)?.get_project_type(), "node");
+
+    // A user type is tried according to its priority
+    registry.register(FnDetector::new(
+        "release-file",
+        200,
+        "RELEASE",
+        |dir: &Path| {
+            let path = dir.join("RELEASE");
+            Ok(path
+                .exists()
+                .then(|| Box::new(VersionFileProject::new(path)) as Box<dyn Project>))
+        },
+    ));
+    assert_eq!(registry.detectors().next().unwrap().name(), "release-file");
+    assert_eq!(registry.detect(dir)?.get_version()?.to_string(), "3.0.0");
+
+    // A type can be forced instead of detected
+    let project = registry.detect_as(dir, "version-file")?;
+    assert_eq!(project.get_version()?.to_string(), "2.0.0");
+    assert!(matches!(
+        registry.detect_as(dir, "cobol").err(),
+        Some(Error::UnknownProjectType { .. })
+    ));
+    assert!(matches!(
+        registry.detect_as(dir, "rust").err(),
+        Some(Error::ProjectTypeNotFound { .. })
+    ));
+
+    let report = Releaser::new(dir)
+        .registry(registry)
+        .project_type("version-file")
+        .dry_run(true)
+        .run()?;
+    assert_eq!(report.new_version, "2.0.1");
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use anyhow::Result;
+use common::git;
+use project_version::bump::BumpType;
+use project_version::config::load_config;
+use project_version::project::{detect_project, NodeProject, PackageManagerCommand, Project};
+use project_version::registry::{FnDetector, ProjectRegistry};
+use project_version::release::{
+    LockfileUpdate, ReleaseEvent, ReleaseOptions, ReleasePlan, Releaser,
+};
+use semver::Version;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use tempfile::tempdir;
+
+#[test]
+fn test_release_plan_and_rollback() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+    let package_json_path = dir.join("package.json");
+    let package_lock_path = dir.join("package-lock.json");
+    let changelog_path = dir.join("CHANGELOG.md");
+    let package_json =
+        r#"{"name": "app", "version": "1.2.3", "homepage": "https://example.com/1.2.3"}"#;
+    fs::write(&package_json_path, package_json)?;
+    fs::write(&package_lock_path, r#"{"name": "app", "version": "1.2.3"}"#)?;
+    fs::write(&changelog_path, "# Changelog\n\n## [Unreleased]\n")?;
+
+    // A rule on the manifest applies on top of the project's own edit
+    fs::write(
+        dir.join(".project-version.toml"),
+        "[[files]]\npath = \"package.json\"\nsearch = \"example.com/{current_version}\"\nreplace = \"example.com/{new_version}\"\n",
+    )?;
+    let config = load_config(dir.to_str().unwrap())?;
+
+    let project = detect_project(dir.to_str().unwrap())?;
+    let options = ReleaseOptions {
+        no_commit: true,
+        offline: true,
+        files: config.files,
+        ..ReleaseOptions::default()
+    };
+    let plan = ReleasePlan::new(
+        project.as_ref(),
+        dir,
+        Version::new(1, 2, 3),
+        Version::new(1, 3, 0),
+        &options,
+    )?;
+
+    // Planning writes nothing
+    assert_eq!(fs::read_to_string(&package_json_path)?, package_json);
+    let paths: Vec<_> = plan.changed_files().map(|change| &change.path).collect();
+    assert_eq!(
+        paths,
+        [&package_json_path, &changelog_path, &package_lock_path]
+    );
+    assert!(plan.changes[0].updated.contains(r#""version": "1.3.0""#));
+    assert!(plan.changes[0].updated.contains("example.com/1.3.0"));
+    assert_eq!(plan.lockfile_update, LockfileUpdate::Offline);
+    assert!(plan.commit_message.is_none() && plan.tag.is_none());
+
+    // Each written file is reported to the observer
+    let mut events = Vec::new();
+    let outcome = plan.execute(&mut |event: &ReleaseEvent| events.push(event.clone()))?;
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1], ReleaseEvent::FileUpdated(changelog_path.clone()));
+    assert!(fs::read_to_string(&package_lock_path)?.contains("1.3.0"));
+    assert!(outcome.files.contains(&changelog_path));
+    assert!(outcome.commit.is_none());
+
+    // A failing package manager puts every file back
+    let mut plan = ReleasePlan::new(
+        project.as_ref(),
+        dir,
+        Version::new(1, 3, 0),
+        Version::new(1, 4, 0),
+        &ReleaseOptions {
+            no_commit: true,
+            ..ReleaseOptions::default()
+        },
+    )?;
+    plan.lockfile_update = LockfileUpdate::Run(PackageManagerCommand::new("sh", &["-c", "exit 1"]));
+    let mut events = Vec::new();
+    let error = plan
+        .execute(&mut |event: &ReleaseEvent| events.push(event.clone()))
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("restored the original files"));
+    assert_eq!(events.last(), Some(&ReleaseEvent::RolledBack));
+    assert!(fs::read_to_string(&package_json_path)?.contains("1.3.0"));
+
+    Ok(())
+}
+
+#[test]
+fn test_releaser_builder() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let package_json_path = temp_dir.path().join("package.json");
+    fs::write(&package_json_path, r#"{"name": "app", "version": "1.2.3"}"#)?;
+
+    // A dry run reports the release without writing anything
+    let report = Releaser::new(temp_dir.path())
+        .bump(BumpType::Minor)
+        .dry_run(true)
+        .run()?;
+    assert_eq!(report.old_version, "1.2.3");
+    assert_eq!(report.new_version, "1.3.0");
+    assert!(report.dry_run);
+    assert_eq!(report.files, vec![package_json_path.clone()]);
+    assert!(fs::read_to_string(&package_json_path)?.contains("1.2.3"));
+
+    // Setting a lower version needs force
+    let lower = Releaser::new(temp_dir.path()).set(Version::new(1, 0, 0));
+    assert!(lower.run().is_err());
+
+    let report = Releaser::new(temp_dir.path())
+        .set(Version::new(1, 0, 0))
+        .force(true)
+        .commit(false)
+        .run()?;
+    assert!(!report.dry_run);
+    assert!(report.commit.is_none() && report.tag.is_none());
+    assert!(fs::read_to_string(&package_json_path)?.contains("1.0.0"));
+
+    // A release can run on another thread, with a custom registry and observer
+    let mut registry = ProjectRegistry::empty();
+    registry.register(FnDetector::new("node", 0, "package.json", |dir: &Path| {
+        Ok(Some(
+            Box::new(NodeProject::new(dir.join("package.json"))) as Box<dyn Project>
+        ))
+    }));
+    let (sender, receiver) = mpsc::channel();
+    let releaser = Releaser::new(temp_dir.path())
+        .registry(registry)
+        .commit(false)
+        .observer(move |event: &ReleaseEvent| sender.send(event.clone()).unwrap());
+    let report = thread::spawn(move || releaser.run()).join().unwrap()?;
+    assert_eq!(report.new_version, "1.0.1");
+    assert_eq!(
+        receiver.recv()?,
+        ReleaseEvent::FileUpdated(package_json_path.clone())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_releaser_commits_in_its_directory() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+    let package_json_path = dir.join("package.json");
+    git(dir, &["init", "-q"])?;
+    git(dir, &["config", "user.name", "Test"])?;
+    git(dir, &["config", "user.email", "test@example.com"])?;
+    fs::write(&package_json_path, r#"{"name": "app", "version": "1.2.3"}"#)?;
+    git(dir, &["add", "package.json"])?;
+    git(dir, &["commit", "-q", "-m", "initial"])?;
+
+    // The test runs from the crate root, outside the project's repository
+    let report = Releaser::new(dir)
+        .bump(BumpType::Minor)
+        .update_lockfiles(false)
+        .run()?;
+    assert_eq!(report.commit, Some(git(dir, &["rev-parse", "HEAD"])?));
+    assert_eq!(report.tag.as_deref(), Some("v1.3.0"));
+    assert_eq!(
+        git(dir, &["log", "-1", "--format=%s"])?,
+        "release: version 1.3.0"
+    );
+    assert_eq!(git(dir, &["tag", "-l", "v1.3.0"])?, "v1.3.0");
+    assert!(git(dir, &["status", "--porcelain"])?.is_empty());
+
+    // A tag that can't be created undoes the commit and puts the files back
+    git(dir, &["tag", "v1.4.0/blocked"])?;
+    let head = git(dir, &["rev-parse", "HEAD"])?;
+    let error = Releaser::new(dir)
+        .bump(BumpType::Minor)
+        .update_lockfiles(false)
+        .run()
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("restored the original files"));
+    assert_eq!(git(dir, &["rev-parse", "HEAD"])?, head);
+    assert!(fs::read_to_string(&package_json_path)?.contains("1.3.0"));
+    assert!(git(dir, &["status", "--porcelain"])?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_release_commits_only_changed_files() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let dir = temp_dir.path();
+    let main_go_path = dir.join("cmd/main.go");
+    fs::create_dir_all(main_go_path.parent().unwrap())?;
+    fs::write(dir.join("go.mod"), "module example.com/foo\n\ngo 1.21\n")?;
+    fs::write(
+        dir.join("version.go"),
+        "package foo\n\nconst Version = \"v1.2.3\"\n",
+    )?;
+    let main_go =
+        "package main\n\nimport \"example.com/foo/internal/util\"\n\nfunc main() { util.Run() }\n";
+    fs::write(&main_go_path, main_go)?;
+    git(dir, &["init", "-q"])?;
+    git(dir, &["config", "user.name", "Test"])?;
+    git(dir, &["config", "user.email", "test@example.com"])?;
+    git(dir, &["add", "-A"])?;
+    git(dir, &["commit", "-q", "-m", "initial"])?;
+
+    // A file importing the module's packages has unrelated local edits
+    fs::write(&main_go_path, format!("{main_go}// wip\n"))?;
+
+    Releaser::new(dir).update_lockfiles(false).run()?;
+    assert_eq!(
+        git(dir, &["show", "--name-only", "--format=", "HEAD"])?,
+        "version.go"
+    );
+    assert_eq!(git(dir, &["status", "--porcelain"])?, "M cmd/main.go");
+
+    Ok(())
+}
@@ -30,6 +30,7 @@ fn test_version_set_with_v_prefix() -> Result<()> {
     assert_eq!(version, Version::new(1, 2, 3));
 
     // Helper function to mimic the version string cleaning in set_version
+    #[allow(clippy::manual_strip)]
     fn parse_version(version_str: &str) -> Result<Version> {
         let clean_version_str = if version_str.starts_with('v') {
             &version_str[1..]
@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use project_version::yaml_edit::{find_scalar, replace_scalar, ScalarStyle, Segment};
+
+#[test]
+fn test_yaml_edit_preserves_quoting_and_comments() -> Result<()> {
+    let content = r#"# Chart for the API
+apiVersion: v2
+annotations:
+  version: 9.9.9
+name: api
+version: "1.2.3" # bumped by release tooling
+appVersion: '1.2.3'
+dependencies:
+- name: db
+  version: 2.0.0
+  repository: file://charts/db
+"#;
+
+    // The root key is found even though a nested `version:` comes first
+    let span = find_scalar(content, &[Segment::Key("version")])?.unwrap();
+    assert_eq!(span.value, "1.2.3");
+    assert_eq!(span.style, ScalarStyle::DoubleQuoted);
+
+    let updated = replace_scalar(content, &[Segment::Key("version")], "1.3.0")?;
+    assert!(updated.contains("version: \"1.3.0\" # bumped by release tooling\n"));
+    assert!(updated.contains("  version: 9.9.9\n"));
+
+    let updated = replace_scalar(&updated, &[Segment::Key("appVersion")], "1.3.0")?;
+    assert!(updated.contains("appVersion: '1.3.0'\n"));
+
+    // Sequence items at the same indentation as their key
+    let path = [
+        Segment::Key("dependencies"),
+        Segment::Index(0),
+        Segment::Key("version"),
+    ];
+    let updated = replace_scalar(&updated, &path, "2.1.0")?;
+    assert!(updated.contains("- name: db\n  version: 2.1.0\n"));
+
+    // Everything else is untouched
+    assert!(updated.starts_with("# Chart for the API\napiVersion: v2\n"));
+    assert!(find_scalar(&updated, &[Segment::Key("missing")])?.is_none());
+
+    Ok(())
+}